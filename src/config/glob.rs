@@ -0,0 +1,129 @@
+//! Find files matching a glob pattern, like `src/**/lib.rs`
+//!
+//! Only what is needed to find a source file is supported: `*` matches any part of a file or
+//! directory name, `?` a single character and `**` any number of directories. Hidden files and
+//! directories are only matched by a pattern starting with a `.`, and symbolic links to
+//! directories are not followed.
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Find the files matching `pattern`, relative to `root`, sorted by path
+pub fn find_files(root: &Path, pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let segments: Vec<&str> = pattern
+        .split(['/', '\\'])
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+
+    let mut result = Vec::new();
+    walk(root, &segments, &mut result)?;
+    result.sort();
+    result.dedup();
+
+    Ok(result)
+}
+
+/// Look for the files matching `segments` in `dir`
+fn walk(dir: &Path, segments: &[&str], result: &mut Vec<PathBuf>) -> io::Result<()> {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+
+    if *segment == "**" {
+        // no directory, then any directory followed by the same pattern
+        walk(dir, rest, result)?;
+        for path in read_dir(dir, "*")? {
+            if is_dir(&path) {
+                walk(&path, segments, result)?;
+            }
+        }
+    } else if !segment.contains(['*', '?']) {
+        visit(&dir.join(segment), rest, result)?;
+    } else {
+        for path in read_dir(dir, segment)? {
+            visit(&path, rest, result)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Add the path if it is a file matching the whole pattern, or look inside it
+fn visit(path: &Path, rest: &[&str], result: &mut Vec<PathBuf>) -> io::Result<()> {
+    if rest.is_empty() {
+        if path.is_file() {
+            result.push(path.to_path_buf());
+        }
+        Ok(())
+    } else if is_dir(path) {
+        walk(path, rest, result)
+    } else {
+        Ok(())
+    }
+}
+
+/// Get the entries of a directory whose names match `pattern`
+fn read_dir(dir: &Path, pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let mut result = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name: Vec<char> = entry.file_name().to_string_lossy().chars().collect();
+        if name.first() == Some(&'.') && pattern.first() != Some(&'.') {
+            continue;
+        }
+        if matches(&pattern, &name) {
+            result.push(entry.path());
+        }
+    }
+
+    Ok(result)
+}
+
+/// Is the path a directory, without following symbolic links
+fn is_dir(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|metadata| metadata.is_dir())
+        .unwrap_or(false)
+}
+
+/// Does a file name match a pattern segment, with `*` and `?` wildcards
+fn matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((&'*', rest)) => (0..=name.len()).any(|skip| matches(rest, &name[skip..])),
+        Some((&'?', rest)) => !name.is_empty() && matches(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    fn matches_str(pattern: &str, name: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        matches(&pattern, &name)
+    }
+
+    #[test]
+    fn match_wildcards() {
+        assert!(matches_str("lib.rs", "lib.rs"));
+        assert!(matches_str("*.rs", "lib.rs"));
+        assert!(matches_str("*", "lib.rs"));
+        assert!(matches_str("l?b.rs", "lib.rs"));
+        assert!(matches_str("*_gen*.rs", "api_generated_v2.rs"));
+        assert!(!matches_str("*.rs", "lib.rs.bak"));
+        assert!(!matches_str("l?b.rs", "lb.rs"));
+        assert!(!matches_str("main.rs", "lib.rs"));
+    }
+}
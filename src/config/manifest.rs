@@ -22,63 +22,206 @@ pub fn get_manifest(project_root: &Path) -> Result<Manifest, String> {
         buf
     };
 
-    let cargo_toml: CargoToml = toml::from_str(&buf).map_err(|e| format!("{}", e))?;
+    parse_manifest(&buf)
+}
+
+/// Is the `Cargo.toml` in `dir` the manifest of a workspace
+///
+/// A manifest that cannot be read or parsed is not considered a workspace.
+pub fn is_workspace_root(dir: &Path) -> bool {
+    let mut buf = String::new();
+    File::open(dir.join("Cargo.toml"))
+        .and_then(|mut cargo_toml| cargo_toml.read_to_string(&mut buf))
+        .ok()
+        .and_then(|_| toml::from_str::<CargoToml>(&buf).ok())
+        .is_some_and(|cargo_toml| cargo_toml.workspace.is_some())
+}
+
+/// Parse the contents of a `Cargo.toml` into the manifest info
+///
+/// Syntax errors and missing required fields are reported with the message from the parser.
+/// A virtual manifest, with `[workspace]` but no `[package]`, is reported with the list of
+/// workspace members, since the documentation has to be read from one of them.
+pub fn parse_manifest(cargo_toml: &str) -> Result<Manifest, String> {
+    let mut cargo_toml: CargoToml =
+        toml::from_str(cargo_toml).map_err(|e| format!("Could not parse Cargo.toml: {}", e))?;
+
+    let package = match cargo_toml.package.take() {
+        Some(package) => package,
+        None => {
+            return Err(match cargo_toml.workspace {
+                Some(workspace) => format!(
+                    "Cargo.toml is a virtual workspace manifest, run from the directory of a \
+                     workspace member or select it with `--root` (members: [{}])",
+                    workspace.members.unwrap_or_default().join(", ")
+                ),
+                None => "Could not parse Cargo.toml: missing field `package`".to_owned(),
+            });
+        }
+    };
 
-    let manifest = Manifest::new(cargo_toml);
+    let manifest = Manifest::new(package, cargo_toml);
 
     Ok(manifest)
 }
 
-#[derive(Debug)]
+/// The crate information, empty by default for a crate without `Cargo.toml`
+#[derive(Debug, Default)]
 pub struct Manifest {
     pub name: String,
     pub license: Option<String>,
+    pub license_file: Option<String>,
     pub lib: Option<ManifestLib>,
     pub bin: Vec<ManifestLib>,
     pub badges: Vec<String>,
-    pub version: String,
+    pub unknown_badges: Vec<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub documentation: Option<String>,
+    pub authors: Vec<String>,
+    pub keywords: Vec<String>,
+    pub categories: Vec<String>,
+    pub readme: ManifestReadme,
+    /// `package.readme` is `false`, the crate has no readme
+    pub readme_disabled: bool,
+    /// Path of `package.readme`, relative to the project root
+    pub readme_path: Option<String>,
 }
 
 impl Manifest {
-    fn new(cargo_toml: CargoToml) -> Manifest {
-        Manifest {
-            name: cargo_toml.package.name,
-            license: cargo_toml.package.license,
-            lib: cargo_toml.lib.map(|lib| ManifestLib::from_cargo_toml(lib)),
-            bin: cargo_toml
+    fn new(package: CargoTomlPackage, cargo_toml: CargoToml) -> Manifest {
+        let bin = {
+            let package_name = &package.name;
+            cargo_toml
                 .bin
                 .map(|bin_vec| {
                     bin_vec
                         .into_iter()
-                        .map(|bin| ManifestLib::from_cargo_toml(bin))
+                        .map(|bin| ManifestLib::from_cargo_toml_bin(bin, package_name))
                         .collect()
                 })
+                .unwrap_or_default()
+        };
+
+        let (badges, unknown_badges) = cargo_toml.badges.map(process_badges).unwrap_or_default();
+
+        let (readme_disabled, readme_path) = match package.readme {
+            Some(CargoTomlReadme::Enabled(enabled)) => (!enabled, None),
+            Some(CargoTomlReadme::Path(path)) => (false, Some(path)),
+            None => (false, None),
+        };
+
+        Manifest {
+            name: package.name,
+            license: package.license.map(CargoTomlLicense::into_expression),
+            license_file: package.license_file,
+            lib: cargo_toml.lib.map(ManifestLib::from_cargo_toml_lib),
+            bin,
+            badges,
+            unknown_badges,
+            version: package.version,
+            description: package.description,
+            repository: package.repository,
+            homepage: package.homepage,
+            documentation: package.documentation,
+            authors: package.authors.unwrap_or_default(),
+            keywords: package.keywords.unwrap_or_default(),
+            categories: package.categories.unwrap_or_default(),
+            readme: package
+                .metadata
+                .and_then(|metadata| metadata.readme)
                 .unwrap_or_default(),
-            badges: cargo_toml
-                .badges
-                .map(|b| process_badges(b))
-                .unwrap_or_default(),
-            version: cargo_toml.package.version,
+            readme_disabled,
+            readme_path,
         }
     }
 }
 
 #[derive(Debug)]
 pub struct ManifestLib {
+    pub name: Option<String>,
     pub path: PathBuf,
     pub doc: bool,
 }
 
 impl ManifestLib {
-    fn from_cargo_toml(lib: CargoTomlLib) -> Self {
+    /// The `[lib]` target, `path` defaults to `src/lib.rs`
+    fn from_cargo_toml_lib(lib: CargoTomlLib) -> Self {
         ManifestLib {
-            path: PathBuf::from(lib.path),
+            path: PathBuf::from(lib.path.unwrap_or_else(|| "src/lib.rs".to_owned())),
+            name: lib.name,
             doc: lib.doc.unwrap_or(true),
         }
     }
+
+    /// A `[[bin]]` target, `path` defaults to `src/main.rs` if the binary has the name of the
+    /// package, or to `src/bin/<name>.rs` otherwise
+    fn from_cargo_toml_bin(bin: CargoTomlLib, package_name: &str) -> Self {
+        let path = match (bin.path, bin.name.as_ref()) {
+            (Some(path), _) => path,
+            (None, Some(name)) if name != package_name => format!("src/bin/{}.rs", name),
+            (None, _) => "src/main.rs".to_owned(),
+        };
+
+        ManifestLib {
+            path: PathBuf::from(path),
+            name: bin.name,
+            doc: bin.doc.unwrap_or(true),
+        }
+    }
+}
+
+/// Settings from the `[package.metadata.readme]` table
+///
+/// They are used as defaults for the corresponding command line options. The options given on
+/// the command line take precedence over the manifest, which takes precedence over the built-in
+/// defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ManifestReadme {
+    pub input: Option<String>,
+    pub template: Option<String>,
+    pub output: Option<String>,
+    pub no_title: Option<bool>,
+    pub append_license: Option<bool>,
+    pub no_indent_headings: Option<bool>,
+    pub heading_base_level: Option<usize>,
+}
+
+impl ManifestReadme {
+    /// Whether the title is added, given if `--no-title` was passed
+    pub fn add_title(&self, no_title: bool) -> bool {
+        !no_title && !self.no_title.unwrap_or(false)
+    }
+
+    /// Whether the license is appended, given if `--no-license` was passed
+    pub fn add_license(&self, no_license: bool) -> bool {
+        !no_license && self.append_license.unwrap_or(true)
+    }
+
+    /// Number of levels added to headings, given the level from the command line, if any
+    ///
+    /// `no-indent-headings` is the same as a `heading-base-level` of 0, like on the command line.
+    pub fn heading_indent(&self, heading_base_level: Option<usize>) -> usize {
+        if let Some(level) = heading_base_level {
+            return level;
+        }
+
+        if self.no_indent_headings.unwrap_or(false) {
+            0
+        } else {
+            self.heading_base_level.unwrap_or(1)
+        }
+    }
 }
 
-fn process_badges(badges: BTreeMap<String, BTreeMap<String, String>>) -> Vec<String> {
+/// Render the known badges, in a fixed order, and return the names of the unknown ones
+fn process_badges(
+    badges: BTreeMap<String, BTreeMap<String, String>>,
+) -> (Vec<String>, Vec<String>) {
+    let mut unknown = Vec::new();
     let mut b: Vec<(u16, _)> = badges
         .into_iter()
         .filter_map(|(name, attrs)| match name.as_ref() {
@@ -96,18 +239,22 @@ fn process_badges(badges: BTreeMap<String, BTreeMap<String, String>>) -> Vec<Str
                 Some((8, badges::is_it_maintained_open_issues(attrs)))
             }
             "maintenance" => Some((9, badges::maintenance(attrs))),
-            _ => return None,
+            _ => {
+                unknown.push(name);
+                None
+            }
         })
         .collect();
 
     b.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-    b.into_iter().map(|(_, badge)| badge).collect()
+    (b.into_iter().map(|(_, badge)| badge).collect(), unknown)
 }
 
 /// Cargo.toml crate information
 #[derive(Clone, Deserialize)]
 struct CargoToml {
-    pub package: CargoTomlPackage,
+    pub package: Option<CargoTomlPackage>,
+    pub workspace: Option<CargoTomlWorkspace>,
     pub lib: Option<CargoTomlLib>,
     pub bin: Option<Vec<CargoTomlLib>>,
     pub badges: Option<BTreeMap<String, BTreeMap<String, String>>>,
@@ -117,13 +264,397 @@ struct CargoToml {
 #[derive(Clone, Deserialize)]
 struct CargoTomlPackage {
     pub name: String,
-    pub license: Option<String>,
-    pub version: String,
+    pub license: Option<CargoTomlLicense>,
+    #[serde(rename = "license-file")]
+    pub license_file: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub documentation: Option<String>,
+    pub authors: Option<Vec<String>>,
+    pub keywords: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub readme: Option<CargoTomlReadme>,
+    pub metadata: Option<CargoTomlMetadata>,
+}
+
+/// Cargo.toml license, either a SPDX expression or, in legacy manifests, a list of licenses
+#[derive(Clone, Deserialize)]
+#[serde(untagged)]
+enum CargoTomlLicense {
+    Expression(String),
+    List(Vec<String>),
+}
+
+impl CargoTomlLicense {
+    /// The license as a SPDX expression, a list is any of its licenses
+    fn into_expression(self) -> String {
+        match self {
+            CargoTomlLicense::Expression(expression) => expression,
+            CargoTomlLicense::List(list) => list.join(" OR "),
+        }
+    }
+}
+
+/// Cargo.toml readme, either a path or a boolean, `false` meaning the crate has no readme
+#[derive(Clone, Deserialize)]
+#[serde(untagged)]
+enum CargoTomlReadme {
+    Enabled(bool),
+    Path(String),
+}
+
+/// Cargo.toml package metadata, only the table used by cargo-readme is read
+#[derive(Clone, Deserialize)]
+struct CargoTomlMetadata {
+    pub readme: Option<ManifestReadme>,
+}
+
+/// Cargo.toml workspace information
+#[derive(Clone, Deserialize)]
+struct CargoTomlWorkspace {
+    pub members: Option<Vec<String>>,
 }
 
 /// Cargo.toml crate lib information
 #[derive(Clone, Deserialize)]
 struct CargoTomlLib {
-    pub path: String,
+    pub name: Option<String>,
+    pub path: Option<String>,
     pub doc: Option<bool>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{parse_manifest, ManifestReadme};
+
+    #[test]
+    fn parse_minimal_manifest() {
+        let result = parse_manifest("[package]\nname = \"my-crate\"");
+        assert!(result.is_ok());
+        assert_eq!("my-crate", result.unwrap().name);
+    }
+
+    #[test]
+    fn parse_garbage_should_fail() {
+        let result = parse_manifest("this is [[not toml");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .starts_with("Could not parse Cargo.toml: "));
+    }
+
+    #[test]
+    fn parse_manifest_without_package_should_fail() {
+        let result = parse_manifest("[dependencies]\nregex = \"1.0\"");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("missing field `package`"));
+    }
+
+    #[test]
+    fn parse_virtual_manifest_should_fail() {
+        let result = parse_manifest("[workspace]\nmembers = [\"core\", \"cli\"]");
+        assert!(result.is_err());
+        assert_eq!(
+            "Cargo.toml is a virtual workspace manifest, run from the directory of a workspace \
+             member or select it with `--root` (members: [core, cli])",
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn parse_workspace_root_with_package() {
+        let result =
+            parse_manifest("[package]\nname = \"my-crate\"\n\n[workspace]\nmembers = [\"cli\"]");
+        assert!(result.is_ok());
+        assert_eq!("my-crate", result.unwrap().name);
+    }
+
+    #[test]
+    fn parse_manifest_without_name_should_fail() {
+        let result = parse_manifest("[package]\nversion = \"0.1.0\"");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("missing field `name`"));
+    }
+
+    #[test]
+    fn parse_manifest_with_readme_disabled() {
+        let result = parse_manifest("[package]\nname = \"my-crate\"\nreadme = false").unwrap();
+        assert!(result.readme_disabled);
+        assert_eq!(None, result.readme_path);
+    }
+
+    #[test]
+    fn parse_manifest_with_readme_path() {
+        let result =
+            parse_manifest("[package]\nname = \"my-crate\"\nreadme = \"docs/README.md\"").unwrap();
+        assert!(!result.readme_disabled);
+        assert_eq!(Some("docs/README.md".to_owned()), result.readme_path);
+    }
+
+    #[test]
+    fn parse_manifest_without_readme() {
+        let result = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        assert!(!result.readme_disabled);
+        assert_eq!(None, result.readme_path);
+    }
+
+    #[test]
+    fn parse_manifest_with_metadata() {
+        let result = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\
+             repository = \"https://github.com/me/my-crate\"\n\
+             homepage = \"https://my-crate.rs\"\n\
+             documentation = \"https://docs.rs/my-crate\"\n\
+             authors = [\"Me <me@example.com>\", \"You\"]",
+        )
+        .unwrap();
+        assert_eq!(
+            Some("https://github.com/me/my-crate".to_owned()),
+            result.repository
+        );
+        assert_eq!(Some("https://my-crate.rs".to_owned()), result.homepage);
+        assert_eq!(
+            Some("https://docs.rs/my-crate".to_owned()),
+            result.documentation
+        );
+        assert_eq!(vec!["Me <me@example.com>", "You"], result.authors);
+    }
+
+    #[test]
+    fn parse_manifest_with_readme_metadata() {
+        let result = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [package.metadata.readme]\n\
+             input = \"src/overview.rs\"\n\
+             template = \"docs/README.tpl\"\n\
+             output = \"docs/README.md\"",
+        )
+        .unwrap();
+        assert_eq!(Some("src/overview.rs".to_owned()), result.readme.input);
+        assert_eq!(Some("docs/README.tpl".to_owned()), result.readme.template);
+        assert_eq!(Some("docs/README.md".to_owned()), result.readme.output);
+    }
+
+    #[test]
+    fn parse_manifest_with_other_metadata() {
+        let result = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [package.metadata.docs.rs]\nall-features = true\n\n\
+             [package.metadata.readme]\ninput = \"src/overview.rs\"",
+        )
+        .unwrap();
+        assert_eq!(Some("src/overview.rs".to_owned()), result.readme.input);
+        assert_eq!(None, result.readme.template);
+        assert_eq!(None, result.readme.output);
+    }
+
+    #[test]
+    fn parse_manifest_without_readme_metadata() {
+        let result = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        assert_eq!(None, result.readme.input);
+        assert_eq!(None, result.readme.template);
+        assert_eq!(None, result.readme.output);
+    }
+
+    #[test]
+    fn parse_manifest_with_readme_options() {
+        let result = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [package.metadata.readme]\n\
+             no-title = true\n\
+             append-license = false\n\
+             no-indent-headings = false\n\
+             heading-base-level = 2",
+        )
+        .unwrap();
+        assert_eq!(Some(true), result.readme.no_title);
+        assert_eq!(Some(false), result.readme.append_license);
+        assert_eq!(Some(false), result.readme.no_indent_headings);
+        assert_eq!(Some(2), result.readme.heading_base_level);
+    }
+
+    #[test]
+    fn readme_options_default() {
+        let readme = ManifestReadme::default();
+        assert!(readme.add_title(false));
+        assert!(readme.add_license(false));
+        assert_eq!(1, readme.heading_indent(None));
+    }
+
+    #[test]
+    fn readme_options_from_manifest() {
+        let readme = ManifestReadme {
+            no_title: Some(true),
+            append_license: Some(false),
+            heading_base_level: Some(2),
+            ..ManifestReadme::default()
+        };
+        assert!(!readme.add_title(false));
+        assert!(!readme.add_license(false));
+        assert_eq!(2, readme.heading_indent(None));
+
+        let readme = ManifestReadme {
+            no_indent_headings: Some(true),
+            heading_base_level: Some(2),
+            ..ManifestReadme::default()
+        };
+        assert_eq!(0, readme.heading_indent(None));
+    }
+
+    #[test]
+    fn readme_options_from_command_line() {
+        let readme = ManifestReadme {
+            no_title: Some(false),
+            append_license: Some(true),
+            no_indent_headings: Some(true),
+            ..ManifestReadme::default()
+        };
+        assert!(!readme.add_title(true));
+        assert!(!readme.add_license(true));
+        assert_eq!(2, readme.heading_indent(Some(2)));
+    }
+
+    #[test]
+    fn parse_manifest_with_badges() {
+        let result = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [badges]\n\
+             maintenance = { status = \"actively-developed\" }\n\
+             travis-ci = { repository = \"me/my-crate\" }",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                "[![Build Status](https://travis-ci.org/me/my-crate.svg?branch=master)]\
+                 (https://travis-ci.org/me/my-crate)",
+                "![Maintenance](https://img.shields.io/badge/maintenance-activly--developed-\
+                 brightgreen.svg)",
+            ],
+            result.badges
+        );
+        assert!(result.unknown_badges.is_empty());
+    }
+
+    #[test]
+    fn parse_manifest_with_unknown_badges() {
+        let result = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [badges]\n\
+             maintenance = { status = \"experimental\" }\n\
+             azure-devops = { project = \"me/my-crate\", pipeline = \"ci\" }",
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["![Maintenance](https://img.shields.io/badge/maintenance-experimental-blue.svg)"],
+            result.badges
+        );
+        assert_eq!(vec!["azure-devops"], result.unknown_badges);
+    }
+
+    #[test]
+    fn parse_manifest_with_single_bin() {
+        let result =
+            parse_manifest("[package]\nname = \"my-crate\"\n\n[[bin]]\nname = \"my-bin\"").unwrap();
+        assert_eq!(1, result.bin.len());
+        assert_eq!(Some("my-bin".to_owned()), result.bin[0].name);
+        assert_eq!(Path::new("src/bin/my-bin.rs"), result.bin[0].path);
+        assert!(result.bin[0].doc);
+    }
+
+    #[test]
+    fn parse_manifest_with_multiple_bins() {
+        let result = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [[bin]]\nname = \"my-crate\"\n\n\
+             [[bin]]\nname = \"tool\"\npath = \"src/tool/main.rs\"\ndoc = false",
+        )
+        .unwrap();
+        let bins: Vec<_> = result
+            .bin
+            .iter()
+            .map(|bin| {
+                (
+                    bin.name.as_ref().unwrap().as_str(),
+                    bin.path.as_path(),
+                    bin.doc,
+                )
+            })
+            .collect();
+        assert_eq!(
+            bins,
+            &[
+                ("my-crate", Path::new("src/main.rs"), true),
+                ("tool", Path::new("src/tool/main.rs"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_manifest_with_lib_without_path() {
+        let result =
+            parse_manifest("[package]\nname = \"my-crate\"\n\n[lib]\nname = \"my_lib\"").unwrap();
+        let lib = result.lib.unwrap();
+        assert_eq!(Some("my_lib".to_owned()), lib.name);
+        assert_eq!(Path::new("src/lib.rs"), lib.path);
+    }
+
+    #[test]
+    fn parse_manifest_with_license() {
+        let result = parse_manifest("[package]\nname = \"my-crate\"\nlicense = \"MIT\"").unwrap();
+        assert_eq!(Some("MIT".to_owned()), result.license);
+        assert_eq!(None, result.license_file);
+    }
+
+    #[test]
+    fn parse_manifest_with_license_list() {
+        let result =
+            parse_manifest("[package]\nname = \"my-crate\"\nlicense = [\"MIT\", \"Apache-2.0\"]")
+                .unwrap();
+        assert_eq!(Some("MIT OR Apache-2.0".to_owned()), result.license);
+    }
+
+    #[test]
+    fn parse_manifest_with_invalid_license_should_fail() {
+        let result = parse_manifest("[package]\nname = \"my-crate\"\nlicense = 42");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_manifest_with_license_file() {
+        let result =
+            parse_manifest("[package]\nname = \"my-crate\"\nlicense-file = \"LICENSE.txt\"")
+                .unwrap();
+        assert_eq!(None, result.license);
+        assert_eq!(Some("LICENSE.txt".to_owned()), result.license_file);
+    }
+
+    #[test]
+    fn parse_manifest_without_license() {
+        let result = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        assert_eq!(None, result.license);
+        assert_eq!(None, result.license_file);
+    }
+
+    #[test]
+    fn parse_manifest_with_keywords_and_categories() {
+        let result = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\
+             keywords = [\"readme\", \"docs\"]\n\
+             categories = [\"development-tools\"]",
+        )
+        .unwrap();
+        assert_eq!(vec!["readme", "docs"], result.keywords);
+        assert_eq!(vec!["development-tools"], result.categories);
+    }
+
+    #[test]
+    fn parse_manifest_without_keywords_and_categories() {
+        let result = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        assert!(result.keywords.is_empty());
+        assert!(result.categories.is_empty());
+    }
+}
@@ -1,6 +1,10 @@
 mod badges;
+mod glob;
 mod manifest;
 pub mod project;
 
 pub use self::manifest::get_manifest;
+pub use self::manifest::parse_manifest;
 pub use self::manifest::Manifest;
+pub use self::manifest::ManifestLib;
+pub use self::manifest::ManifestReadme;
@@ -1,7 +1,9 @@
 use std::env;
-use std::path::{Path, PathBuf};
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
 
-use config::manifest::{Manifest, ManifestLib};
+use config::glob;
+use config::manifest::{self, Manifest, ManifestLib};
+use log;
 
 /// Get the project root from given path or defaults to current directory
 ///
@@ -9,82 +11,676 @@ use config::manifest::{Manifest, ManifestLib};
 /// as is. If no path is given, the current directory is used.
 /// A `Cargo.toml` file must be present is the root directory.
 pub fn get_root(given_root: Option<&str>) -> Result<PathBuf, String> {
-    let current_dir = env::current_dir().map_err(|e| format!("{}", e))?;
-    let root = match given_root {
-        Some(root) => {
-            let root = Path::new(root);
-            if root.is_absolute() {
-                root.to_path_buf()
-            } else {
-                current_dir.join(root)
-            }
-        }
-        None => current_dir,
-    };
+    let root = resolve_root(given_root)?;
 
     if !root.join("Cargo.toml").is_file() {
         return Err(format!(
-            "`{:?}` does not look like a Rust/Cargo project",
-            root
+            "`{}` does not look like a Rust/Cargo project",
+            display_path(&root)
         ));
     }
 
     Ok(root)
 }
 
-/// Find the default entrypoiny to read the doc comments from
+/// Find the default template `name` in the project root or in one of its parent directories
 ///
-/// Try to read entrypoint in the following order:
-/// - src/lib.rs
-/// - src/main.rs
+/// The search stops at the nearest parent directory with a `Cargo.toml`, whose template is only
+/// used if it is the root of a workspace, so a crate nested in another project does not pick the
+/// template of that project.
+pub fn find_default_template(project_root: &Path, name: &str) -> Option<PathBuf> {
+    for dir in project_root.ancestors() {
+        let is_parent_project = dir != project_root && dir.join("Cargo.toml").is_file();
+        if is_parent_project && !manifest::is_workspace_root(dir) {
+            return None;
+        }
+
+        let template = dir.join(name);
+        if template.is_file() {
+            return Some(template);
+        }
+        if is_parent_project {
+            return None;
+        }
+    }
+
+    None
+}
+
+/// Get the project root like `get_root`, without requiring a `Cargo.toml` in it
+pub fn resolve_root(given_root: Option<&str>) -> Result<PathBuf, String> {
+    let current_dir = env::current_dir().map_err(|e| format!("{}", e))?;
+    Ok(match given_root {
+        Some(root) => resolve_path(&current_dir, root),
+        None => current_dir,
+    })
+}
+
+/// Resolve a path given by the user or in `Cargo.toml` against the project root
+///
+/// Relative paths are joined to `project_root`, absolute paths are used as is.
+pub fn resolve_path<P: AsRef<Path>>(project_root: &Path, path: P) -> PathBuf {
+    let path = path.as_ref();
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        project_root.join(path)
+    }
+}
+
+/// Format a path for messages, with `/` as separator on every platform
+pub fn display_path(path: &Path) -> String {
+    path.to_string_lossy().replace(MAIN_SEPARATOR, "/")
+}
+
+/// Which target to read the docs from when a crate has both a library and a binary
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Prefer {
+    Lib,
+    Bin,
+}
+
+/// Find the default entrypoint to read the doc comments from
+///
+/// The paths declared in `Cargo.toml` take precedence over the conventional ones. The library is
+/// found in the following order:
 /// - file defined in the `[lib]` section of Cargo.toml
+/// - src/lib.rs
+///
+/// And the binary in the following order:
 /// - file defined in the `[[bin]]` section of Cargo.toml, if there is only one
-///   - if there is more than one `[[bin]]`, an error is returned
-pub fn find_entrypoint(current_dir: &Path, manifest: &Manifest) -> Result<PathBuf, String> {
-    // try lib.rs
-    let lib_rs = current_dir.join("src/lib.rs");
-    if lib_rs.exists() {
-        return Ok(lib_rs);
-    }
+///   - if there is more than one `[[bin]]`, the one with the name of the package is used, like
+///     `src/main.rs`
+/// - src/main.rs
+///
+/// If the crate has both, the one given by `prefer` is used. Without a preference, the library
+/// is used and a warning is printed, since the docs of the binary are silently ignored otherwise.
+///
+/// If there is more than one `[[bin]]` and none of them could be chosen, an error is returned.
+/// The returned path is joined to `project_root`, since the paths in `Cargo.toml` are relative
+/// to it.
+pub fn find_entrypoint(
+    project_root: &Path,
+    manifest: &Manifest,
+    prefer: Option<Prefer>,
+) -> Result<PathBuf, String> {
+    let bin_list: Vec<_> = manifest.bin.iter().filter(|b| b.doc).collect();
 
-    // try main.rs
-    let main_rs = current_dir.join("src/main.rs");
-    if main_rs.exists() {
-        return Ok(main_rs);
+    let lib = find_lib(project_root, manifest);
+    let bin = find_bin(project_root, manifest, &bin_list);
+
+    match (lib, bin) {
+        (Some(lib), Some(bin)) => match prefer {
+            Some(Prefer::Lib) => Ok(lib),
+            Some(Prefer::Bin) => Ok(bin),
+            None => {
+                log::warn(&format!(
+                    "Found both a library ({}) and a binary ({}), reading the docs of the \
+                     library; use `--prefer bin` or `--input` to choose",
+                    relative_display_path(project_root, &lib),
+                    relative_display_path(project_root, &bin)
+                ));
+                Ok(lib)
+            }
+        },
+        (Some(lib), None) => Ok(lib),
+        (None, Some(bin)) => Ok(bin),
+        (None, None) => {
+            if bin_list.len() > 1 {
+                let paths = bin_list
+                    .iter()
+                    .map(|b| display_path(&b.path))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(format!("Multiple binaries found, choose one: [{}]", paths));
+            }
+
+            // if no entrypoint is found, return an error
+            Err("No entrypoint found".to_owned())
+        }
     }
+}
 
+/// Find the entrypoint of the library, see `find_entrypoint`
+fn find_lib(project_root: &Path, manifest: &Manifest) -> Option<PathBuf> {
     // try lib defined in `Cargo.toml`
     if let Some(ManifestLib {
         path: ref lib,
         doc: true,
+        ..
     }) = manifest.lib
     {
-        return Ok(lib.to_path_buf());
+        return Some(resolve_path(project_root, lib));
     }
 
+    // try lib.rs
+    let lib_rs = project_root.join("src/lib.rs");
+    if lib_rs.exists() {
+        return Some(lib_rs);
+    }
+
+    None
+}
+
+/// Find the entrypoint of the binary among the documented ones, see `find_entrypoint`
+fn find_bin(
+    project_root: &Path,
+    manifest: &Manifest,
+    bin_list: &[&ManifestLib],
+) -> Option<PathBuf> {
     // try bin defined in `Cargo.toml`
-    if manifest.bin.len() > 0 {
-        let mut bin_list: Vec<_> = manifest
-            .bin
+    let bin = if bin_list.len() > 1 {
+        // the binary with the name of the package takes the place of `src/main.rs`
+        bin_list
             .iter()
-            .filter(|b| b.doc == true)
-            .map(|b| b.path.clone())
-            .collect();
+            .find(|b| b.name.as_ref() == Some(&manifest.name))
+    } else {
+        bin_list.first()
+    };
+    if let Some(bin) = bin {
+        return Some(resolve_path(project_root, &bin.path));
+    }
+
+    // try main.rs
+    let main_rs = project_root.join("src/main.rs");
+    if main_rs.exists() {
+        return Some(main_rs);
+    }
+
+    None
+}
+
+/// Find the entrypoint of the binary with the given name
+///
+/// The binaries are the `[[bin]]` of `Cargo.toml` and, like cargo does, `src/main.rs` for the
+/// binary with the name of the package and `src/bin/<name>.rs` or `src/bin/<name>/main.rs` for
+/// the others. An error listing the binaries found is returned if none has the name.
+pub fn find_bin_by_name(
+    project_root: &Path,
+    manifest: &Manifest,
+    name: &str,
+) -> Result<PathBuf, String> {
+    let bins = list_bins(project_root, manifest);
+
+    match bins.iter().find(|(bin_name, _)| bin_name == name) {
+        Some((_, path)) => Ok(path.clone()),
+        None if bins.is_empty() => Err(format!(
+            "No binary named `{}`, the crate has no binaries",
+            name
+        )),
+        None => Err(format!(
+            "No binary named `{}`, choose one of: {}",
+            name,
+            bins.iter()
+                .map(|(bin_name, _)| bin_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// List the names and entrypoints of the binaries, see `find_bin_by_name`
+fn list_bins(project_root: &Path, manifest: &Manifest) -> Vec<(String, PathBuf)> {
+    let mut bins: Vec<(String, PathBuf)> = manifest
+        .bin
+        .iter()
+        .map(|bin| {
+            let name = bin.name.clone().unwrap_or_else(|| manifest.name.clone());
+            (name, resolve_path(project_root, &bin.path))
+        })
+        .collect();
+
+    let mut discovered = Vec::new();
+    if project_root.join("src/main.rs").is_file() {
+        discovered.push((manifest.name.clone(), project_root.join("src/main.rs")));
+    }
+    if let Ok(entries) = project_root.join("src/bin").read_dir() {
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        paths.sort();
+        for path in paths {
+            let name = match path.file_stem() {
+                Some(stem) => stem.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "rs") {
+                discovered.push((name, path));
+            } else if path.join("main.rs").is_file() {
+                discovered.push((name, path.join("main.rs")));
+            }
+        }
+    }
+
+    // the binaries declared in `Cargo.toml` take precedence over the discovered ones
+    for (name, path) in discovered {
+        if !bins.iter().any(|(bin_name, _)| *bin_name == name) {
+            bins.push((name, path));
+        }
+    }
+
+    bins
+}
+
+/// Find the single file matching a glob pattern, like `src/**/lib.rs`, in the project root
+///
+/// An error is returned if no file or more than one file match.
+pub fn find_glob(project_root: &Path, pattern: &str) -> Result<PathBuf, String> {
+    let mut files = glob::find_files(project_root, pattern)
+        .map_err(|e| format!("Could not look for files matching '{}': {}", pattern, e))?;
 
-        if bin_list.len() > 1 {
-            let paths = bin_list
+    match files.len() {
+        0 => Err(format!("No file matches '{}'", pattern)),
+        1 => Ok(files.remove(0)),
+        _ => Err(format!(
+            "More than one file matches '{}': {}",
+            pattern,
+            files
                 .iter()
-                .map(|p| p.to_string_lossy())
+                .map(|file| relative_display_path(project_root, file))
                 .collect::<Vec<_>>()
-                .join(", ");
-            return Err(format!("Multiple binaries found, choose one: [{}]", paths));
+                .join(", ")
+        )),
+    }
+}
+
+/// Find the files with the text of the crate license
+///
+/// The `license-file` of `Cargo.toml` is used if given. Otherwise, each license of the SPDX
+/// expression is looked for in a `LICENSE-<ID>` file, like `LICENSE-MIT`, or in a file named after
+/// the first part of the identifier, like `LICENSE-APACHE` for `Apache-2.0`. A single `LICENSE`
+/// file is used if none of them is found. Each name can have a `.md` or `.txt` extension.
+///
+/// An error is returned if the text of a license is not found. A crate without license has no
+/// license files.
+pub fn find_license_files(
+    project_root: &Path,
+    manifest: &Manifest,
+) -> Result<Vec<PathBuf>, String> {
+    if let Some(ref license_file) = manifest.license_file {
+        let path = resolve_path(project_root, license_file);
+        if !path.is_file() {
+            return Err(format!(
+                "License file '{}' not found",
+                relative_display_path(project_root, &path)
+            ));
+        }
+        return Ok(vec![path]);
+    }
+
+    let license = match manifest.license {
+        Some(ref license) => license,
+        None => return Ok(Vec::new()),
+    };
+
+    let ids: Vec<&str> = license
+        .split(|c: char| c == '(' || c == ')' || c.is_whitespace())
+        .filter(|id| !id.is_empty() && !["OR", "AND", "WITH", "or", "and", "with"].contains(id))
+        .collect();
+
+    let mut files = Vec::new();
+    let mut missing = Vec::new();
+    for id in &ids {
+        let short_id = id.split('-').next().unwrap_or(id);
+        let names = &[format!("LICENSE-{}", id), format!("LICENSE-{}", short_id)];
+        match find_file(project_root, names) {
+            Some(path) => {
+                if !files.contains(&path) {
+                    files.push(path);
+                }
+            }
+            None => missing.push(*id),
         }
+    }
+
+    if missing.is_empty() {
+        return Ok(files);
+    }
 
-        if let Some(bin) = bin_list.pop() {
-            return Ok(bin);
+    if files.is_empty() {
+        if let Some(path) = find_file(project_root, &["LICENSE".to_owned()]) {
+            return Ok(vec![path]);
         }
     }
 
-    // if no entrypoint is found, return an error
-    Err("No entrypoint found".to_owned())
+    Err(format!(
+        "Could not find the text of the license `{}`, expected a file like 'LICENSE-{}' or \
+         'LICENSE' in the project root",
+        missing[0],
+        missing[0]
+            .split('-')
+            .next()
+            .unwrap_or(missing[0])
+            .to_uppercase()
+    ))
+}
+
+/// Find the first existing file among the given names, with or without a `.md` or `.txt`
+/// extension, case-insensitively
+fn find_file(project_root: &Path, names: &[String]) -> Option<PathBuf> {
+    let mut entries: Vec<PathBuf> = project_root
+        .read_dir()
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    entries.sort();
+
+    names.iter().find_map(|name| {
+        let name = name.to_lowercase();
+        entries
+            .iter()
+            .find(|path| {
+                let file_name = path
+                    .file_name()
+                    .map(|file_name| file_name.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                [".md", ".txt", ""]
+                    .iter()
+                    .any(|extension| file_name == format!("{}{}", name, extension))
+            })
+            .cloned()
+    })
+}
+
+/// Format a path relative to the project root for messages, if it is inside it
+fn relative_display_path(project_root: &Path, path: &Path) -> String {
+    display_path(path.strip_prefix(project_root).unwrap_or(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use super::{
+        display_path, find_bin_by_name, find_default_template, find_entrypoint, find_glob,
+        find_license_files, resolve_path, Prefer,
+    };
+    use config::parse_manifest;
+
+    // a project root without `src/lib.rs` or `src/main.rs`
+    const NO_DEFAULT_ENTRYPOINT: &str = "/nonexistent";
+
+    #[test]
+    fn find_single_bin() {
+        let manifest =
+            parse_manifest("[package]\nname = \"my-crate\"\n\n[[bin]]\nname = \"tool\"").unwrap();
+        let result = find_entrypoint(Path::new(NO_DEFAULT_ENTRYPOINT), &manifest, None);
+        assert_eq!(
+            Ok(Path::new("/nonexistent/src/bin/tool.rs").to_path_buf()),
+            result
+        );
+    }
+
+    #[test]
+    fn find_bin_with_package_name() {
+        let manifest = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [[bin]]\nname = \"tool\"\n\n\
+             [[bin]]\nname = \"my-crate\"\npath = \"src/cli.rs\"",
+        )
+        .unwrap();
+        let result = find_entrypoint(Path::new(NO_DEFAULT_ENTRYPOINT), &manifest, None);
+        assert_eq!(
+            Ok(Path::new("/nonexistent/src/cli.rs").to_path_buf()),
+            result
+        );
+    }
+
+    #[test]
+    fn find_multiple_bins_should_fail() {
+        let manifest = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [[bin]]\nname = \"tool\"\n\n\
+             [[bin]]\nname = \"other\"\npath = \"src/other.rs\"",
+        )
+        .unwrap();
+        let result = find_entrypoint(Path::new(NO_DEFAULT_ENTRYPOINT), &manifest, None);
+        assert_eq!(
+            Err("Multiple binaries found, choose one: [src/bin/tool.rs, src/other.rs]".to_owned()),
+            result
+        );
+    }
+
+    #[test]
+    fn find_lib_with_custom_path() {
+        let manifest = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [lib]\npath = \"src/core.rs\"\n\n\
+             [[bin]]\nname = \"tool\"",
+        )
+        .unwrap();
+        let result = find_entrypoint(Path::new(NO_DEFAULT_ENTRYPOINT), &manifest, None);
+        assert_eq!(
+            Ok(Path::new("/nonexistent/src/core.rs").to_path_buf()),
+            result
+        );
+    }
+
+    #[test]
+    fn find_lib_with_custom_path_before_default() {
+        let project_root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/entrypoint-resolution/custom-lib");
+        let manifest =
+            parse_manifest("[package]\nname = \"my-crate\"\n\n[lib]\npath = \"src/core.rs\"")
+                .unwrap();
+        let result = find_entrypoint(&project_root, &manifest, None);
+        assert_eq!(Ok(project_root.join("src/core.rs")), result);
+    }
+
+    #[test]
+    fn find_lib_without_custom_path() {
+        let project_root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/entrypoint-resolution/custom-lib");
+        let manifest = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        let result = find_entrypoint(&project_root, &manifest, None);
+        assert_eq!(Ok(project_root.join("src/lib.rs")), result);
+    }
+
+    #[test]
+    fn find_lib_and_main_without_preference() {
+        let project_root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/entrypoint-resolution/lib");
+        let manifest = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        let result = find_entrypoint(&project_root, &manifest, None);
+        assert_eq!(Ok(project_root.join("src/lib.rs")), result);
+    }
+
+    #[test]
+    fn find_lib_and_main_prefer_lib() {
+        let project_root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/entrypoint-resolution/lib");
+        let manifest = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        let result = find_entrypoint(&project_root, &manifest, Some(Prefer::Lib));
+        assert_eq!(Ok(project_root.join("src/lib.rs")), result);
+    }
+
+    #[test]
+    fn find_lib_and_main_prefer_bin() {
+        let project_root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/entrypoint-resolution/lib");
+        let manifest = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        let result = find_entrypoint(&project_root, &manifest, Some(Prefer::Bin));
+        assert_eq!(Ok(project_root.join("src/main.rs")), result);
+    }
+
+    #[test]
+    fn find_only_lib_prefer_bin() {
+        let project_root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/entrypoint-resolution/custom-lib");
+        let manifest = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+        let result = find_entrypoint(&project_root, &manifest, Some(Prefer::Bin));
+        assert_eq!(Ok(project_root.join("src/lib.rs")), result);
+    }
+
+    #[test]
+    fn resolve_relative_path() {
+        let result = resolve_path(Path::new("/project"), "src/lib.rs");
+        assert_eq!(Path::new("/project/src/lib.rs"), result);
+    }
+
+    #[test]
+    fn resolve_absolute_path() {
+        let result = resolve_path(Path::new("/project"), "/other/src/lib.rs");
+        assert_eq!(Path::new("/other/src/lib.rs"), result);
+    }
+
+    #[test]
+    fn find_lib_with_absolute_path() {
+        let manifest =
+            parse_manifest("[package]\nname = \"my-crate\"\n\n[lib]\npath = \"/shared/lib.rs\"")
+                .unwrap();
+        let result = find_entrypoint(Path::new(NO_DEFAULT_ENTRYPOINT), &manifest, None);
+        assert_eq!(Ok(Path::new("/shared/lib.rs").to_path_buf()), result);
+    }
+
+    #[test]
+    fn display_path_with_slashes() {
+        let path = Path::new("/project").join("src").join("lib.rs");
+        assert_eq!("/project/src/lib.rs", display_path(&path));
+    }
+
+    /// Create a project in a temporary directory with the given files
+    fn temp_project(name: &str, files: &[&str]) -> PathBuf {
+        let root = env::temp_dir().join(format!("cargo-readme-{}-{}", name, std::process::id()));
+        for file in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "//! docs").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn find_default_template_in_project_root() {
+        let root = temp_project("template-root", &["README.tpl", "member/README.tpl"]);
+        let result = find_default_template(&root.join("member"), "README.tpl");
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(Some(root.join("member/README.tpl")), result);
+    }
+
+    #[test]
+    fn find_default_template_two_levels_up() {
+        let root = temp_project(
+            "template-workspace",
+            &["README.tpl", "crates/member/Cargo.toml"],
+        );
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]",
+        )
+        .unwrap();
+        let result = find_default_template(&root.join("crates/member"), "README.tpl");
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(Some(root.join("README.tpl")), result);
+    }
+
+    #[test]
+    fn find_default_template_stops_at_parent_project() {
+        let root = temp_project(
+            "template-parent",
+            &["README.tpl", "Cargo.toml", "crates/member/Cargo.toml"],
+        );
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"parent\"").unwrap();
+        let result = find_default_template(&root.join("crates/member"), "README.tpl");
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(None, result);
+    }
+
+    #[test]
+    fn find_glob_single_match() {
+        let root = temp_project(
+            "glob-single",
+            &[
+                "src/generated/v2/lib.rs",
+                "src/generated/v2/api.rs",
+                "src/main.rs",
+            ],
+        );
+        let result = find_glob(&root, "src/**/lib.rs");
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(Ok(root.join("src/generated/v2/lib.rs")), result);
+    }
+
+    #[test]
+    fn find_glob_no_match_should_fail() {
+        let root = temp_project("glob-none", &["src/main.rs"]);
+        let result = find_glob(&root, "src/**/lib.rs");
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(Err("No file matches 'src/**/lib.rs'".to_owned()), result);
+    }
+
+    #[test]
+    fn find_glob_multiple_matches_should_fail() {
+        let root = temp_project("glob-multiple", &["src/lib.rs", "src/v2/lib.rs"]);
+        let result = find_glob(&root, "src/**/lib.rs");
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            Err("More than one file matches 'src/**/lib.rs': src/lib.rs, src/v2/lib.rs".to_owned()),
+            result
+        );
+    }
+
+    #[test]
+    fn find_license_files_of_each_license() {
+        let root = temp_project(
+            "license-files",
+            &["LICENSE-MIT", "license-apache.md", "LICENSE"],
+        );
+        let manifest =
+            parse_manifest("[package]\nname = \"my-crate\"\nlicense = \"MIT OR Apache-2.0\"")
+                .unwrap();
+        let result = find_license_files(&root, &manifest);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            Ok(vec![
+                root.join("LICENSE-MIT"),
+                root.join("license-apache.md")
+            ]),
+            result
+        );
+    }
+
+    #[test]
+    fn find_single_license_file() {
+        let root = temp_project("license-single", &["LICENSE.txt"]);
+        let manifest =
+            parse_manifest("[package]\nname = \"my-crate\"\nlicense = \"MIT/Apache-2.0\"").unwrap();
+        let result = find_license_files(&root, &manifest);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(Ok(vec![root.join("LICENSE.txt")]), result);
+    }
+
+    #[test]
+    fn find_missing_license_file_should_fail() {
+        let root = temp_project("license-missing", &["LICENSE-MIT"]);
+        let manifest =
+            parse_manifest("[package]\nname = \"my-crate\"\nlicense-file = \"COPYING\"").unwrap();
+        let result = find_license_files(&root, &manifest);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(Err("License file 'COPYING' not found".to_owned()), result);
+    }
+
+    #[test]
+    fn find_bin_by_name_in_manifest() {
+        let manifest = parse_manifest(
+            "[package]\nname = \"my-crate\"\n\n\
+             [[bin]]\nname = \"tool\"\n\n\
+             [[bin]]\nname = \"cli\"\npath = \"src/cli.rs\"",
+        )
+        .unwrap();
+        let root = Path::new(NO_DEFAULT_ENTRYPOINT);
+        assert_eq!(
+            Ok(Path::new("/nonexistent/src/bin/tool.rs").to_path_buf()),
+            find_bin_by_name(root, &manifest, "tool")
+        );
+        assert_eq!(
+            Ok(Path::new("/nonexistent/src/cli.rs").to_path_buf()),
+            find_bin_by_name(root, &manifest, "cli")
+        );
+        assert_eq!(
+            Err("No binary named `other`, choose one of: tool, cli".to_owned()),
+            find_bin_by_name(root, &manifest, "other")
+        );
+    }
 }
@@ -0,0 +1,338 @@
+//! Line based diff between the current README and the generated one
+//!
+//! The diff is the longest common subsequence of the lines, formatted as a unified diff.
+
+/// Number of unchanged lines shown around the changes
+const CONTEXT: usize = 3;
+
+// ANSI escape sequences coloring the removed and added lines
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// A line of the diff
+#[derive(Debug, PartialEq)]
+pub enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Compute the line diff turning `old` into `new`
+///
+/// Lines common to the start and the end of both texts are skipped before looking for the longest
+/// common subsequence, so the usual small changes to a README are cheap.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<Line<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|&(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|&(a, b)| a == b)
+        .count();
+
+    let mut result: Vec<Line> = old[..prefix].iter().map(|&l| Line::Same(l)).collect();
+    result.extend(lcs_diff(
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    ));
+    result.extend(old[old.len() - suffix..].iter().map(|&l| Line::Same(l)));
+
+    result
+}
+
+/// Diff of the lines using a table of the longest common subsequences of their suffixes
+fn lcs_diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let width = new.len() + 1;
+    let mut lengths = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            result.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            result.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            result.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|&l| Line::Removed(l)));
+    result.extend(new[j..].iter().map(|&l| Line::Added(l)));
+
+    result
+}
+
+/// Format the diff of `old` and `new` as a unified diff of `filename`
+///
+/// An empty string is returned if the texts are the same. With `color`, the removed lines are red
+/// and the added lines green. When the lines are the same, the texts differ by their line endings
+/// or by the newline at the end, which is shown like GNU diff does, with a "\ No newline at end of
+/// file" marker.
+pub fn unified_diff(filename: &str, old: &str, new: &str, color: bool) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let lines = diff_lines(old, new);
+
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|&(_, line)| is_added(line) || is_removed(line))
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut result = format!("--- {}\n+++ {} (generated)\n", filename, filename);
+
+    if changes.is_empty() {
+        result.push_str(&line_ending_diff(&lines, old, new, color));
+        return result;
+    }
+
+    // group the changes into hunks, merging them when their context would overlap
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &index in &changes {
+        let start = index.saturating_sub(CONTEXT);
+        let end = (index + 1 + CONTEXT).min(lines.len());
+        match hunks.last_mut() {
+            Some(hunk) if start <= hunk.1 => hunk.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        // line numbers where the hunk starts in each text
+        let old_start = lines[..start].iter().filter(|line| !is_added(line)).count();
+        let new_start = lines[..start]
+            .iter()
+            .filter(|line| !is_removed(line))
+            .count();
+        let hunk = &lines[start..end];
+        let old_count = hunk.iter().filter(|line| !is_added(line)).count();
+        let new_count = hunk.iter().filter(|line| !is_removed(line)).count();
+
+        result.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for line in hunk {
+            let (marker, line_color) = match *line {
+                Line::Same(_) => (' ', None),
+                Line::Removed(_) => ('-', Some(RED)),
+                Line::Added(_) => ('+', Some(GREEN)),
+            };
+            match line_color {
+                Some(line_color) if color => result.push_str(&format!(
+                    "{}{}{}{}\n",
+                    line_color,
+                    marker,
+                    line_text(line),
+                    RESET
+                )),
+                _ => result.push_str(&format!("{}{}\n", marker, line_text(line))),
+            }
+        }
+    }
+
+    result
+}
+
+/// Format the difference of texts with the same lines, which is either in their line endings or
+/// in the newline at the end
+fn line_ending_diff(lines: &[Line], old: &str, new: &str, color: bool) -> String {
+    if old.replace("\r\n", "\n") == new.replace("\r\n", "\n") {
+        return "\\ Only the line endings differ, the generated file uses \"\\n\"\n".to_owned();
+    }
+
+    // the last line is the same in both texts, and only one of them ends with a newline
+    let last = lines.len();
+    let text = lines.last().map_or("", line_text);
+    let no_newline = "\\ No newline at end of file\n";
+    let (removed, added) = if color {
+        (
+            format!("{}-{}{}\n", RED, text, RESET),
+            format!("{}+{}{}\n", GREEN, text, RESET),
+        )
+    } else {
+        (format!("-{}\n", text), format!("+{}\n", text))
+    };
+
+    let mut result = format!(
+        "@@ -{} +{} @@\n",
+        hunk_range(last - 1, 1),
+        hunk_range(last - 1, 1)
+    );
+    result.push_str(&removed);
+    if !old.ends_with('\n') {
+        result.push_str(no_newline);
+    }
+    result.push_str(&added);
+    if !new.ends_with('\n') {
+        result.push_str(no_newline);
+    }
+
+    result
+}
+
+/// Range of a hunk header, like "3,2"; an empty range starts at the line before it
+fn hunk_range(start: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{},0", start)
+    } else {
+        format!("{},{}", start + 1, count)
+    }
+}
+
+fn line_text<'a>(line: &Line<'a>) -> &'a str {
+    match *line {
+        Line::Same(text) | Line::Removed(text) | Line::Added(text) => text,
+    }
+}
+
+fn is_added(line: &Line) -> bool {
+    match *line {
+        Line::Added(_) => true,
+        _ => false,
+    }
+}
+
+fn is_removed(line: &Line) -> bool {
+    match *line {
+        Line::Removed(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Line::{Added, Removed, Same};
+    use super::{diff_lines, unified_diff};
+
+    #[test]
+    fn diff_same_text() {
+        assert_eq!(diff_lines("a\nb", "a\nb"), &[Same("a"), Same("b")]);
+        assert_eq!(unified_diff("README.md", "a\nb", "a\nb", false), "");
+    }
+
+    #[test]
+    fn diff_insertion() {
+        assert_eq!(
+            diff_lines("a\nc", "a\nb\nc"),
+            &[Same("a"), Added("b"), Same("c")]
+        );
+    }
+
+    #[test]
+    fn diff_deletion() {
+        assert_eq!(
+            diff_lines("a\nb\nc", "a\nc"),
+            &[Same("a"), Removed("b"), Same("c")]
+        );
+    }
+
+    #[test]
+    fn diff_modification() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "a\nB\nc\nD"),
+            &[
+                Same("a"),
+                Removed("b"),
+                Added("B"),
+                Same("c"),
+                Removed("d"),
+                Added("D"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_moved_line() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "b\nc\na\nd"),
+            &[Removed("a"), Same("b"), Same("c"), Added("a"), Same("d"),]
+        );
+    }
+
+    #[test]
+    fn unified_diff_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n10\n11\n12\n13";
+        assert_eq!(
+            unified_diff("README.md", old, new, false),
+            "--- README.md\n\
+             +++ README.md (generated)\n\
+             @@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n\
+             @@ -10,3 +10,4 @@\n 10\n 11\n 12\n+13\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_merges_close_changes() {
+        let old = "1\n2\n3\n4\n5";
+        let new = "one\n2\n3\n4\nfive";
+        assert_eq!(
+            unified_diff("README.md", old, new, false),
+            "--- README.md\n\
+             +++ README.md (generated)\n\
+             @@ -1,5 +1,5 @@\n-1\n+one\n 2\n 3\n 4\n-5\n+five\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_missing_newline_at_end() {
+        assert_eq!(
+            unified_diff("README.md", "a\nb", "a\nb\n", false),
+            "--- README.md\n\
+             +++ README.md (generated)\n\
+             @@ -2,1 +2,1 @@\n-b\n\\ No newline at end of file\n+b\n"
+        );
+        assert_eq!(
+            unified_diff("README.md", "a\nb\n", "a\nb", false),
+            "--- README.md\n\
+             +++ README.md (generated)\n\
+             @@ -2,1 +2,1 @@\n-b\n+b\n\\ No newline at end of file\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_line_endings() {
+        assert_eq!(
+            unified_diff("README.md", "a\r\nb\r\n", "a\nb\n", false),
+            "--- README.md\n\
+             +++ README.md (generated)\n\
+             \\ Only the line endings differ, the generated file uses \"\\n\"\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_with_color() {
+        assert_eq!(
+            unified_diff("README.md", "a\nb", "a\nB", true),
+            "--- README.md\n\
+             +++ README.md (generated)\n\
+             @@ -1,2 +1,2 @@\n a\n\x1b[31m-b\x1b[0m\n\x1b[32m+B\x1b[0m\n"
+        );
+    }
+}
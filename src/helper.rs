@@ -1,115 +1,425 @@
-use std::fs::File;
-use std::io::{ErrorKind, Write};
+use std::fs::{self, File, Permissions};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use cargo_readme::get_manifest;
-use cargo_readme::project;
+use cargo_readme::included_files;
+use cargo_readme::log;
+use cargo_readme::project::{self, Prefer};
+use cargo_readme::TEMPLATE_TAGS;
+
+use diff;
 
 const DEFAULT_TEMPLATE: &'static str = "README.tpl";
+const DEFAULT_OUTPUT: &'static str = "README.md";
+const STDIN: &'static str = "-";
 
 /// Get the project root from given path or defaults to current directory
 ///
 /// The given path is appended to the current directory if is a relative path, otherwise it is used
 /// as is. If no path is given, the current directory is used.
-/// A `Cargo.toml` file must be present is the root directory.
-pub fn get_project_root(given_root: Option<&str>) -> Result<PathBuf, String> {
-    project::get_root(given_root)
-}
-
-/// Get the source file from which the doc comments will be extracted
-pub fn get_source(project_root: &Path, input: Option<&str>) -> Result<File, String> {
-    match input {
-        Some(input) => {
-            let input = project_root.join(input);
-            File::open(&input)
-                .map_err(|e| format!("Could not open file '{}': {}", input.to_string_lossy(), e))
-        }
-        None => find_entrypoint(&project_root),
+/// A `Cargo.toml` file must be present is the root directory if `require_manifest` is set.
+pub fn get_project_root(
+    given_root: Option<&str>,
+    require_manifest: bool,
+) -> Result<PathBuf, String> {
+    if require_manifest {
+        project::get_root(given_root)
+    } else {
+        project::resolve_root(given_root)
+    }
+}
+
+/// Get the sources from which the doc comments will be extracted, with their paths
+///
+/// If an input is `-`, the doc comments are read from stdin. If no input is given, the
+/// entrypoint of the crate is used, see `find_entrypoint`.
+pub fn get_sources(
+    project_root: &Path,
+    inputs: Option<Vec<&str>>,
+    prefer: Option<Prefer>,
+) -> Result<Vec<(Option<PathBuf>, Box<dyn Read>)>, String> {
+    match inputs {
+        Some(inputs) => inputs
+            .into_iter()
+            .map(|input| get_source(project_root, input))
+            .collect(),
+        None => find_entrypoint(&project_root, prefer)
+            .map(|(path, f)| vec![(Some(path), Box::new(f) as Box<dyn Read>)]),
+    }
+}
+
+/// Get a source from which the doc comments will be extracted, stdin has no path
+fn get_source(
+    project_root: &Path,
+    input: &str,
+) -> Result<(Option<PathBuf>, Box<dyn Read>), String> {
+    if input == STDIN {
+        log::info("Reading docs from stdin");
+        return Ok((None, Box::new(io::stdin())));
     }
+
+    let input = project::resolve_path(project_root, input);
+    log::info(&format!(
+        "Reading docs from '{}'",
+        project::display_path(&input)
+    ));
+    File::open(&input)
+        .map(|f| (Some(input.clone()), Box::new(f) as Box<dyn Read>))
+        .map_err(|e| {
+            format!(
+                "Could not open file '{}': {}",
+                project::display_path(&input),
+                e
+            )
+        })
 }
 
-/// Get the destination file where the result will be output to
-pub fn get_dest(project_root: &Path, output: Option<&str>) -> Result<Option<File>, String> {
-    match output {
-        Some(filename) => {
-            let output = project_root.join(filename);
-            File::create(&output).map(|f| Some(f)).map_err(|e| {
+/// Get the path of the destination file where the result will be output to
+///
+/// If no output is given, `README.md` in the project root is used. If `mkdir` is set, the missing
+/// parent directories of the output file are created. The file itself is only written by
+/// `write_output`, once the result is generated.
+pub fn get_dest(project_root: &Path, output: Option<&str>, mkdir: bool) -> Result<PathBuf, String> {
+    let output = project::resolve_path(project_root, output.unwrap_or(DEFAULT_OUTPUT));
+    log::info(&format!(
+        "Writing output to '{}'",
+        project::display_path(&output)
+    ));
+
+    if mkdir {
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
                 format!(
-                    "Could not create output file '{}': {}",
-                    output.to_string_lossy(),
+                    "Could not create directory '{}': {}",
+                    project::display_path(&parent),
                     e
                 )
-            })
+            })?;
         }
-        None => Ok(None),
     }
+
+    Ok(output)
+}
+
+/// Whether `dest` was modified after the sources, the files they include in their docs with
+/// `include_str!` and the template, so it does not need to be generated again
+///
+/// A source read from stdin, or a file whose modification time cannot be read, is never older
+/// than `dest`.
+pub fn is_up_to_date(dest: &Path, sources: &[Option<PathBuf>], template: Option<&Path>) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+
+    let dest_modified = match modified(dest) {
+        Ok(time) => time,
+        Err(_) => return false,
+    };
+
+    let included: Vec<PathBuf> = sources
+        .iter()
+        .filter_map(Option::as_ref)
+        .flat_map(|source| included_files(source))
+        .collect();
+
+    sources
+        .iter()
+        .map(|source| source.as_ref().map(AsRef::as_ref))
+        .chain(included.iter().map(|path| Some(path.as_ref())))
+        .chain(template.map(Some))
+        .all(|input| match input.map(modified) {
+            Some(Ok(time)) => time < dest_modified,
+            _ => false,
+        })
 }
 
-/// Get the template file that will be used to render the output
+/// Get the template file that will be used to render the output, with its path
 pub fn get_template_file(
     project_root: &Path,
     template: Option<&str>,
-) -> Result<Option<File>, String> {
-    match template {
+) -> Result<Option<(PathBuf, Box<dyn Read>)>, String> {
+    let template = match template {
+        // stdin is reserved for the source file
+        Some(STDIN) => return Err("Template cannot be read from stdin".to_owned()),
         // template path was given, try to read it
-        Some(template) => {
-            let template = project_root.join(template);
-            File::open(&template).map(|f| Some(f)).map_err(|e| {
-                format!(
-                    "Could not open template file '{}': {}",
-                    template.to_string_lossy(),
-                    e
-                )
-            })
-        }
-        // try to read the defautl template file
+        Some(template) => project::resolve_path(project_root, template),
+        // try to read the default template file, from the project root or its workspace root
+        None => match project::find_default_template(project_root, DEFAULT_TEMPLATE) {
+            Some(template) => template,
+            // default template not found, return `None`
+            None => {
+                log::info("No template found, using the default layout");
+                return Ok(None);
+            }
+        },
+    };
+
+    log::info(&format!(
+        "Using template '{}'",
+        project::display_path(&template)
+    ));
+    File::open(&template)
+        .map(|f| Some((template.clone(), Box::new(f) as Box<dyn Read>)))
+        .map_err(|e| {
+            format!(
+                "Could not open template file '{}': {}",
+                project::display_path(&template),
+                e
+            )
+        })
+}
+
+/// List the template tags, one per line with their description aligned
+pub fn list_template_tags() -> String {
+    let width = TEMPLATE_TAGS
+        .iter()
+        .map(|&(tag, _)| tag.len())
+        .max()
+        .unwrap_or(0);
+
+    TEMPLATE_TAGS
+        .iter()
+        .map(|&(tag, description)| format!("{:width$}  {}\n", tag, description, width = width))
+        .collect()
+}
+
+/// Get the current date in UTC, formatted as ISO 8601, like `2024-05-01`
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Convert a number of days since 1970-01-01 into a year, month and day
+///
+/// This is the `civil_from_days` algorithm from <http://howardhinnant.github.io/date_algorithms.html>,
+/// restricted to dates after 1970.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // days since 0000-03-01, so the leap day is the last day of the year
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+/// Write result to output, either stdout or destination file
+///
+/// A read-only destination file is only overwritten if `force` is set, and is then made writable.
+pub fn write_output(dest: Option<&Path>, readme: String, force: bool) -> Result<(), String> {
+    match dest {
+        Some(dest) => write_file_atomically(dest, readme.as_bytes(), force),
         None => {
-            let template = project_root.join(DEFAULT_TEMPLATE);
-            match File::open(&template) {
-                Ok(file) => Ok(Some(file)),
-                // do not generate an error on file not found
-                Err(ref e) if e.kind() != ErrorKind::NotFound => {
+            log::info("Writing output to stdout");
+            print!("{}", readme);
+            Ok(())
+        }
+    }
+}
+
+/// Write to a temporary file next to `dest` and rename it over `dest`
+///
+/// This way `dest` is never left truncated or half written if something goes wrong. The
+/// permissions of an existing `dest` are kept, except for the read-only bit cleared by `force`.
+fn write_file_atomically(dest: &Path, contents: &[u8], force: bool) -> Result<(), String> {
+    // renaming over a read-only file works, so it is checked first
+    let permissions = match fs::metadata(dest) {
+        Ok(metadata) => {
+            let mut permissions = metadata.permissions();
+            if permissions.readonly() {
+                if !force {
                     return Err(format!(
-                        "Could not open template file '{}': {}",
-                        DEFAULT_TEMPLATE, e
-                    ))
+                        "Output file '{}' is read-only, use `--force` to overwrite it",
+                        project::display_path(dest)
+                    ));
                 }
-                // default template not found, return `None`
-                _ => Ok(None),
+                make_writable(&mut permissions);
             }
+            Some(permissions)
         }
+        Err(_) => None,
+    };
+
+    let file_name = dest
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = dest.with_file_name(format!(".{}.cargo-readme.tmp", file_name));
+
+    let mut file = File::create(&temp).map_err(|e| {
+        format!(
+            "Could not create output file '{}': {}",
+            project::display_path(dest),
+            e
+        )
+    })?;
+
+    let written = file.write_all(contents).and_then(|_| file.sync_all());
+    // the file is closed before renaming it, which fails for open files on Windows
+    drop(file);
+
+    let result = written
+        .and_then(|_| match permissions {
+            Some(permissions) => fs::set_permissions(&temp, permissions),
+            None => Ok(()),
+        })
+        .and_then(|_| fs::rename(&temp, dest));
+
+    result.map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!(
+            "Could not write to output file '{}': {}",
+            project::display_path(dest),
+            e
+        )
+    })
+}
+
+/// Clear the read-only bit, only for the owner on unix
+fn make_writable(permissions: &mut Permissions) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = permissions.mode();
+        permissions.set_mode(mode | 0o200);
     }
+    #[cfg(not(unix))]
+    permissions.set_readonly(false);
 }
 
-/// Write result to output, either stdout or destination file
-pub fn write_output(dest: &mut Option<File>, readme: String) -> Result<(), String> {
-    match dest.as_mut() {
-        Some(dest) => {
-            let mut bytes = readme.into_bytes();
-            // Append new line at end of file to match behavior of `cargo readme > README.md`
-            bytes.push(b'\n');
-
-            dest.write_all(&mut bytes)
-                .map(|_| ())
-                .map_err(|e| format!("Could not write to output file: {}", e))?;
-        }
-        None => println!("{}", readme),
+/// Check that the output file, `README.md` by default, has the same content as the result
+///
+/// If the contents differ, a diff is printed to stdout, colored if `color` is set, and an error is
+/// returned.
+pub fn check_output(
+    project_root: &Path,
+    output: Option<&str>,
+    readme: &str,
+    color: bool,
+) -> Result<(), String> {
+    let filename = output.unwrap_or(DEFAULT_OUTPUT);
+    let output = project::resolve_path(project_root, filename);
+    log::info(&format!("Checking '{}'", project::display_path(&output)));
+
+    let current = read_output(&output)?;
+    if current == readme.as_bytes() {
+        return Ok(());
     }
 
+    print!(
+        "{}",
+        diff::unified_diff(filename, &String::from_utf8_lossy(&current), readme, color)
+    );
+
+    Err(format!(
+        "Output file '{}' is not up to date",
+        project::display_path(&output)
+    ))
+}
+
+/// Print the diff between the output file, `README.md` by default, and the result
+///
+/// Nothing is printed if the contents are the same. A missing output file is considered empty,
+/// since it would be created.
+pub fn diff_output(
+    project_root: &Path,
+    output: Option<&str>,
+    readme: &str,
+    color: bool,
+) -> Result<(), String> {
+    let filename = output.unwrap_or(DEFAULT_OUTPUT);
+    let output = project::resolve_path(project_root, filename);
+    log::info(&format!(
+        "Comparing with '{}'",
+        project::display_path(&output)
+    ));
+
+    let current = if output.exists() {
+        read_output(&output)?
+    } else {
+        Vec::new()
+    };
+
+    print!(
+        "{}",
+        diff::unified_diff(filename, &String::from_utf8_lossy(&current), readme, color)
+    );
+
     Ok(())
 }
 
-/// Find the default entrypoiny to read the doc comments from
+/// Read the current content of the output file
+fn read_output(output: &Path) -> Result<Vec<u8>, String> {
+    let mut current = Vec::new();
+    File::open(output)
+        .and_then(|mut f| f.read_to_end(&mut current))
+        .map_err(|e| {
+            format!(
+                "Could not read output file '{}': {}",
+                project::display_path(output),
+                e
+            )
+        })?;
+
+    Ok(current)
+}
+
+/// Find the default entrypoint to read the doc comments from
 ///
 /// Try to read entrypoint in the following order:
-/// - src/lib.rs
-/// - src/main.rs
 /// - file defined in the `[lib]` section of Cargo.toml
+/// - src/lib.rs
 /// - file defined in the `[[bin]]` section of Cargo.toml, if there is only one
-///   - if there is more than one `[[bin]]`, an error is returned
-pub fn find_entrypoint(current_dir: &Path) -> Result<File, String> {
-    let manifest = get_manifest(current_dir)?;
-    let entrypoint = project::find_entrypoint(current_dir, &manifest)?;
+///   - if there is more than one `[[bin]]`, the one with the name of the package is used
+/// - src/main.rs
+///
+/// If there are both a library and a binary, the one given by `prefer` is used.
+pub fn find_entrypoint(
+    project_root: &Path,
+    prefer: Option<Prefer>,
+) -> Result<(PathBuf, File), String> {
+    let manifest = get_manifest(project_root)?;
+    let entrypoint = project::find_entrypoint(project_root, &manifest, prefer)?;
+    log::info(&format!(
+        "Reading docs from '{}'",
+        project::display_path(&entrypoint)
+    ));
+
+    let file = File::open(&entrypoint).map_err(|e| {
+        format!(
+            "Could not open file '{}': {}",
+            project::display_path(&entrypoint),
+            e
+        )
+    })?;
 
-    File::open(current_dir.join(entrypoint)).map_err(|e| format!("{}", e))
+    Ok((entrypoint, file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::civil_from_days;
+
+    #[test]
+    fn civil_from_days_known_dates() {
+        assert_eq!((1970, 1, 1), civil_from_days(0));
+        assert_eq!((2000, 2, 29), civil_from_days(11_016));
+        assert_eq!((2000, 3, 1), civil_from_days(11_017));
+        assert_eq!((2024, 5, 1), civil_from_days(19_844));
+        assert_eq!((2100, 3, 1), civil_from_days(47_541));
+    }
 }
@@ -19,11 +19,11 @@
 //! With `cargo-readme`, you just write the rustdoc, run the tests, and then run:
 //!
 //! ```sh
-//! cargo readme > README.md
+//! cargo readme
 //! ```
 //!
 //! And that's it! Your `README.md` is populated with the contents of the doc comments from your
-//! `lib.rs` (or `main.rs`).
+//! `lib.rs` (or `main.rs`). Use `--stdout` to print the result instead of writing the file.
 //!
 //! # Usage
 //!
@@ -135,6 +135,26 @@
 //!
 //! By default, `README.tpl` will be used as the template, but you can override it using the
 //! `--template` to choose a different template or `--no-template` to disable it.
+//!
+//! # Configuration
+//!
+//! The options that are the same on every run can be set in a `[package.metadata.readme]` table in
+//! `Cargo.toml`, and are used when the corresponding command line option is not given:
+//!
+//! ```toml
+//! [package.metadata.readme]
+//! input = "src/overview.rs"
+//! template = "docs/README.tpl"
+//! output = "docs/README.md"
+//! no-title = true
+//! append-license = false
+//! heading-base-level = 2
+//! ```
+//!
+//! `no-indent-headings = true` is the same as `heading-base-level = 0`. Options given on the
+//! command line take precedence over the manifest, which takes precedence over the built-in
+//! defaults. For example, `--heading-base-level 1` overrides `heading-base-level = 2`, and
+//! `--no-license` disables the license even with `append-license = true`.
 
 #[macro_use]
 extern crate serde_derive;
@@ -146,8 +166,12 @@ extern crate regex;
 extern crate toml;
 
 mod config;
+pub mod log;
 mod readme;
 
+// Library API, so build scripts and other tools can generate the README without the binary
 pub use config::get_manifest;
+pub use config::parse_manifest;
 pub use config::project;
-pub use readme::generate_readme;
+pub use config::{Manifest, ManifestLib, ManifestReadme};
+pub use readme::{generate_readme, included_files, render, Format, ReadmeOptions, TEMPLATE_TAGS};
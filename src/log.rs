@@ -0,0 +1,65 @@
+//! Diagnostics printed to stderr, so stdout stays clean for the output
+//!
+//! The amount of messages is controlled by `--quiet` and `--verbose`. Errors are not printed here,
+//! they are returned to the caller.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How much is printed to stderr
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Verbosity {
+    /// Nothing but errors
+    Quiet,
+    /// Warnings, the default
+    Normal,
+    /// Warnings and what the files used are
+    Verbose,
+}
+
+static VERBOSITY: AtomicUsize = AtomicUsize::new(Verbosity::Normal as usize);
+
+/// Set the verbosity of the messages printed from now on
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as usize, Ordering::Relaxed);
+}
+
+/// Get the current verbosity
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose,
+    }
+}
+
+/// Print a warning, unless quiet
+pub fn warn(message: &str) {
+    if verbosity() >= Verbosity::Normal {
+        eprintln!("Warning: {}", message);
+    }
+}
+
+/// Print a message about what is done, unless quiet
+pub fn notice(message: &str) {
+    if verbosity() >= Verbosity::Normal {
+        eprintln!("{}", message);
+    }
+}
+
+/// Print an informative message, only if verbose
+pub fn info(message: &str) {
+    if verbosity() >= Verbosity::Verbose {
+        eprintln!("{}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Verbosity;
+
+    #[test]
+    fn verbosity_order() {
+        assert!(Verbosity::Quiet < Verbosity::Normal);
+        assert!(Verbosity::Normal < Verbosity::Verbose);
+    }
+}
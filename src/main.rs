@@ -1,15 +1,25 @@
 //! Generate README.md from doc comments.
 
+extern crate atty;
 #[macro_use]
 extern crate clap;
 
 extern crate cargo_readme;
 
-use std::io::{self, Write};
+use std::env;
+use std::ffi::OsString;
+use std::io::{self, Read, Write};
 
+use cargo_readme::log::{self, Verbosity};
+use cargo_readme::project::{self, Prefer};
+use cargo_readme::{Format, Manifest, ReadmeOptions};
 use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 
+mod diff;
 mod helper;
+mod report;
+
+use report::Report;
 
 fn main() {
     let matches = App::new("cargo-readme")
@@ -28,16 +38,88 @@ fn main() {
                 .short("i")
                 .long("input")
                 .takes_value(true)
-                .help("File to read from.{n}\
-                       If not provided, will try to use `src/lib.rs`, then `src/main.rs`. If \
-                       neither file could be found, will look into `Cargo.toml` for a `[lib]`, \
-                       then for a single `[[bin]]`. If multiple binaries are found, an error \
-                       will be returned."))
+                .multiple(true)
+                .number_of_values(1)
+                .help("File to read from, relative to the project root, or `-` to read from \
+                       stdin.{n}\
+                       Can be given more than once, the docs of each file are concatenated in \
+                       order.{n}\
+                       If not provided, will use the `input` of `[package.metadata.readme]` in \
+                       `Cargo.toml`, then the `[lib]` from `Cargo.toml`, then `src/lib.rs`, \
+                       then a single `[[bin]]` from `Cargo.toml` or the one with the name of the \
+                       package, then `src/main.rs`. If multiple binaries are found and none can \
+                       be chosen, an error will be returned.{n}\
+                       With an input, the project root needs no `Cargo.toml` if nothing from it \
+                       is output, like with `--no-title` and no template."))
+            .arg(Arg::with_name("INPUT_SEPARATOR")
+                .long("input-separator")
+                .value_name("TEXT")
+                .allow_hyphen_values(true)
+                .default_value("\\n")
+                .help("Text between the docs of two inputs, where `\\n` is a new line.{n}\
+                       The default is a blank line. Another separator, like '---' for a \
+                       horizontal rule or a heading, is written between blank lines."))
+            .arg(Arg::with_name("INPUT_GLOB")
+                .long("input-glob")
+                .takes_value(true)
+                .value_name("PATTERN")
+                .conflicts_with("INPUT")
+                .help("Read from the single file matching a pattern, like 'src/**/lib.rs'.{n}\
+                       The pattern is relative to the project root, `*` matches any part of a \
+                       name and `**` any number of directories. It is an error if no file or \
+                       more than one file match."))
+            .arg(Arg::with_name("BIN")
+                .long("bin")
+                .takes_value(true)
+                .value_name("NAME")
+                .conflicts_with_all(&["INPUT", "INPUT_GLOB", "PREFER"])
+                .help("Read from the entrypoint of the binary with the given name.{n}\
+                       The binary is a `[[bin]]` from `Cargo.toml`, `src/main.rs` for the one \
+                       with the name of the package, or `src/bin/<NAME>.rs`."))
+            .arg(Arg::with_name("PREFER")
+                .long("prefer")
+                .value_name("TARGET")
+                .possible_values(&["lib", "bin"])
+                .help("Target to read the docs from when the crate has both a library and a \
+                       binary.{n}\
+                       If not provided, the library is used with a warning. Ignored when an \
+                       input is given."))
             .arg(Arg::with_name("OUTPUT")
                 .short("o")
                 .long("output")
                 .takes_value(true)
-                .help("File to write to. If not provided, will output to stdout."))
+                .help("File to write to, relative to the project root.{n}\
+                       If not provided, the `output` of `[package.metadata.readme]` in \
+                       `Cargo.toml` is used, or `README.md`. Use `--stdout` to output to stdout \
+                       instead."))
+            .arg(Arg::with_name("STDOUT")
+                .long("stdout")
+                .conflicts_with_all(&["OUTPUT", "CHECK", "DIFF"])
+                .help("Output to stdout instead of writing to `README.md`."))
+            .arg(Arg::with_name("FORCE")
+                .long("force")
+                .conflicts_with_all(&["STDOUT", "CHECK", "DIFF"])
+                .help("Overwrite the output file even if it is read-only.{n}\
+                       The output file is made writable. Without this, a read-only output file \
+                       is an error."))
+            .arg(Arg::with_name("IF_CHANGED")
+                .long("if-changed")
+                .conflicts_with_all(&["STDOUT", "CHECK", "DIFF"])
+                .help("Only write the output file if it is older than the inputs, the files they \
+                       include with `include_str!` or the template.{n}\
+                       Changes to Cargo.toml, like a new version, are not noticed."))
+            .arg(Arg::with_name("MKDIR")
+                .long("mkdir")
+                .conflicts_with("STDOUT")
+                .help("Create the missing parent directories of the output file."))
+            .arg(Arg::with_name("FORMAT")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["markdown", "rst"])
+                .default_value("markdown")
+                .help("Format of the output.{n}\
+                       With 'rst', the markdown is converted into reStructuredText and the \
+                       output defaults to `README.rst`."))
             .arg(Arg::with_name("ROOT")
                 .short("r")
                 .long("project-root")
@@ -49,13 +131,36 @@ fn main() {
                 .long("template")
                 .takes_value(true)
                 .conflicts_with("NO_TEMPLATE")
-                .help("Template used to render the output.{n}\
-                       Default behavior is to use `README.tpl` if it exists."))
+                .help("Template used to render the output, relative to the project root.{n}\
+                       Default behavior is to use the `template` of `[package.metadata.readme]` \
+                       in `Cargo.toml`, then the CARGO_README_TEMPLATE environment variable, or \
+                       `README.tpl` if it exists in the project root or in the root of its \
+                       workspace. Unlike `--input`, the template cannot be read from stdin."))
             .arg(Arg::with_name("NO_TITLE")
                 .long("no-title")
                 .help("Do not prepend title line.{n}\
                        By default, the title ('# crate-name') is prepended to the output.{n}\
                        Ignored when using a template."))
+            .arg(Arg::with_name("TITLE_FORMAT")
+                .long("title-format")
+                .value_name("FORMAT")
+                .default_value("# {name}\\n\\n")
+                .help("Format of the title, where `{name}` is the crate name and `\\n` a new \
+                       line.{n}\
+                       The format includes the new lines separating the title from the rest \
+                       of the readme.{n}\
+                       Ignored when using a template."))
+            .arg(Arg::with_name("TITLE_CASE")
+                .long("title-case")
+                .help("Write the crate name as words in title case.{n}\
+                       Like 'My Cool Crate' for 'my-cool_crate', in the title and in the \
+                       `{{crate}}` tag of the template."))
+            .arg(Arg::with_name("DEDUP_TITLE")
+                .long("dedup-title")
+                .conflicts_with("NO_TITLE")
+                .help("Remove the first heading of the docs if it is the crate name.{n}\
+                       This prevents the crate name from appearing twice when the docs already \
+                       start with it."))
             .arg(Arg::with_name("NO_BADGES")
                 .long("no-badges")
                 .help("Do not prepend badges line.{n}\
@@ -66,68 +171,520 @@ fn main() {
                 .help("Do not append license line.{n}\
                        By default, the license defined in `Cargo.toml` will be prepended to the output.{n}\
                        Ignored when using a template."))
+            .arg(Arg::with_name("LICENSE_PROSE")
+                .long("license-prose")
+                .help("Write the license line as a sentence.{n}\
+                       License expressions like 'MIT OR Apache-2.0' are written as \
+                       'Licensed under either of MIT or Apache-2.0', without the license \
+                       prefix.{n}\
+                       Ignored when using a template."))
+            .arg(Arg::with_name("LICENSE_HEADING")
+                .long("license-heading")
+                .help("Append the license in a 'License' section instead of a single line.{n}\
+                       The section heading has the same level as the top headings of the docs.{n}\
+                       Ignored when using a template."))
+            .arg(Arg::with_name("LICENSE_BODY")
+                .long("license-body")
+                .conflicts_with("NO_LICENSE")
+                .help("Append the text of the license files in a 'License' section.{n}\
+                       The files are `license-file` from `Cargo.toml`, or `LICENSE-<ID>` files, \
+                       like 'LICENSE-MIT' or 'LICENSE-APACHE', or a single `LICENSE` file in \
+                       the project root.{n}\
+                       Ignored when using a template."))
+            .arg(Arg::with_name("LICENSE_PREFIX")
+                .long("license-prefix")
+                .value_name("TEXT")
+                .default_value("License: ")
+                .help("Text written before the license in the license line.{n}\
+                       Ignored when using a template or `--license-heading`. Cannot be used \
+                       with `--license-prose`, whose sentence replaces the prefix."))
+            .arg(Arg::with_name("LICENSE_LINK")
+                .long("license-link")
+                .help("Write the license ids as links to their page on spdx.org.{n}\
+                       Each id of an expression like 'MIT OR Apache-2.0' is linked, a license \
+                       which is not a SPDX expression is written as is, after the license \
+                       prefix. This also applies to the `{{license}}` tag of the template."))
+            .arg(Arg::with_name("APPEND_METADATA")
+                .long("append-metadata")
+                .help("Append the keywords and categories from `Cargo.toml`.{n}\
+                       Ignored when using a template."))
             .arg(Arg::with_name("NO_TEMPLATE")
                 .long("no-template")
                 .help("Ignore template file when generating README.{n}\
                        Only useful to ignore default template `README.tpl`."))
             .arg(Arg::with_name("NO_INDENT_HEADINGS")
                 .long("no-indent-headings")
+                .conflicts_with("HEADING_BASE_LEVEL")
                 .help("Do not add an extra level to headings.{n}\
                        By default, '#' headings become '##', so the first '#' can be the crate \
-                       name. Use this option to prevent this behavior.")))
-        .get_matches();
+                       name. Use this option to prevent this behavior.{n}\
+                       Same as `--heading-base-level 0`."))
+            .arg(Arg::with_name("HEADING_BASE_LEVEL")
+                .long("heading-base-level")
+                .takes_value(true)
+                .value_name("N")
+                .help("Number of levels added to headings.{n}\
+                       Defaults to 1, so '#' headings become '##'."))
+            .arg(Arg::with_name("NO_INDENT_HEADING")
+                .long("no-indent-heading")
+                .takes_value(true)
+                .value_name("TEXT")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Leave the headings with this text at their level.{n}\
+                       Like `--no-indent-heading Examples`, to keep '# Examples' while the other \
+                       headings get an extra level. The text is compared case-insensitively. \
+                       Can be given more than once."))
+            .arg(Arg::with_name("NO_STRIP_HIDDEN")
+                .long("no-strip-hidden")
+                .help("Do not remove hidden lines from rust code blocks.{n}\
+                       By default, lines starting with '# ' in rust code blocks are hidden, like \
+                       in rustdoc. With this option they are kept, without the '# ' marker."))
+            .arg(Arg::with_name("FENCE_INDENTED")
+                .long("fence-indented")
+                .help("Convert indented code blocks into '```rust' code blocks.{n}\
+                       Indented code blocks are rust code for rustdoc, but are not highlighted \
+                       when rendered as markdown."))
+            .arg(Arg::with_name("RUST_FENCE_LABEL")
+                .long("rust-fence-label")
+                .takes_value(true)
+                .value_name("LABEL")
+                .default_value("rust")
+                .help("Language label of rust code blocks.{n}\
+                       Some platforms prefer 'rs'. An empty label leaves the code blocks without \
+                       a language."))
+            .arg(Arg::with_name("EXPAND_INTRA_LINKS")
+                .long("expand-intra-links")
+                .help("Rewrite intra-doc links to point to the documentation on docs.rs.{n}\
+                       Links like '[`Item`]' or '[text](crate::module::Item)' only work in \
+                       rustdoc, so they are changed into links to the page of the item when its \
+                       kind is given, like 'struct@Item', 'macro!' or 'func()', and otherwise to \
+                       a search for the item, since its kind is not known."))
+            .arg(Arg::with_name("REWRITE_RELATIVE_LINKS")
+                .long("rewrite-relative-links")
+                .help("Rewrite the links to files of the repository into absolute urls.{n}\
+                       Like '[guide](docs/guide.md)' into a link to \
+                       '<repository>/blob/HEAD/docs/guide.md', using the `repository` of \
+                       `Cargo.toml`, so they work on crates.io. Anchors, urls and links in code \
+                       are left alone."))
+            .arg(Arg::with_name("GFM_ALERTS")
+                .long("gfm-alerts")
+                .help("Rewrite the blockquotes starting with 'Note:' or 'Warning:' into GitHub \
+                       alerts.{n}\
+                       Like '> Note: text' into '> [!NOTE]' followed by '> text'. The keywords \
+                       are Note, Tip, Important, Warning and Caution."))
+            .arg(Arg::with_name("EXCLUDE_SECTION")
+                .long("exclude-section")
+                .takes_value(true)
+                .value_name("NAME")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Remove the section with this heading from the docs.{n}\
+                       The section goes up to the next heading of the same or a higher level, \
+                       so its subsections are removed too. The heading text is compared \
+                       case-insensitively. Can be given more than once."))
+            .arg(Arg::with_name("STRIP_HTML_COMMENTS")
+                .long("strip-html-comments")
+                .help("Remove the HTML comments, like '<!-- ... -->', from the docs.{n}\
+                       Comments spanning several lines are removed too, but not the ones in code \
+                       blocks."))
+            .arg(Arg::with_name("ADD_ANCHORS")
+                .long("add-anchors")
+                .help("Add an html anchor before each heading.{n}\
+                       The anchors are named like the ones generated by GitHub, for renderers \
+                       that do not generate them."))
+            .arg(Arg::with_name("SQUEEZE_BLANK_LINES")
+                .long("squeeze-blank-lines")
+                .help("Collapse consecutive blank lines into a single one.{n}\
+                       Blank lines inside code blocks are kept."))
+            .arg(Arg::with_name("WRAP")
+                .long("wrap")
+                .takes_value(true)
+                .value_name("N")
+                .help("Reflow paragraphs to a maximum width of N columns.{n}\
+                       Code blocks, headings, lists, tables and quotes are kept as they are."))
+            .arg(Arg::with_name("OUTER_DOCS")
+                .long("outer-docs")
+                .help("Also read '///' doc comments, not only '//!'.{n}\
+                       Useful when the input is a file documenting a single item, like a \
+                       re-export."))
+            .arg(Arg::with_name("INCLUDE_MODULES")
+                .long("include-modules")
+                .help("Also read the '//!' docs of the modules declared inline.{n}\
+                       The docs of each `mod name { ... }` are appended after the docs of the \
+                       crate, under a heading with the module name."))
+            .arg(Arg::with_name("ALLOW_EMPTY")
+                .long("allow-empty")
+                .help("Generate the README even if no doc comments were found.{n}\
+                       By default, this is an error, since it usually means the wrong source \
+                       file was used."))
+            .arg(Arg::with_name("STRICT")
+                .long("strict")
+                .help("Fail on problems that are only warnings by default, like unknown tags in \
+                       the template or a code block not closed at the end of the docs."))
+            .arg(Arg::with_name("QUIET")
+                .short("q")
+                .long("quiet")
+                .conflicts_with("VERBOSE")
+                .help("Do not print anything but errors to stderr."))
+            .arg(Arg::with_name("VERBOSE")
+                .short("v")
+                .long("verbose")
+                .help("Print the files read and written to stderr.{n}\
+                       This includes the source files, the template, if any, and the output \
+                       file."))
+            .arg(Arg::with_name("CHECK")
+                .long("check")
+                .help("Check that the output file is up to date instead of writing to it.{n}\
+                       The output file is the one given by `--output`, or `README.md` if not \
+                       provided. If it differs from the generated content, a diff is printed \
+                       and the process exits with an error. The `{{date}}` tag of the template \
+                       is kept as it is, unless the date is set with `CARGO_README_DATE`."))
+            .arg(Arg::with_name("DIFF")
+                .long("diff")
+                .conflicts_with_all(&["CHECK", "MKDIR"])
+                .help("Print the changes to the output file instead of writing to it.{n}\
+                       The output file is the one given by `--output`, or `README.md` if not \
+                       provided. Unlike `--check`, this never fails because of differences. Like \
+                       with `--check`, the `{{date}}` tag of the template is kept as it is, \
+                       unless the date is set with `CARGO_README_DATE`."))
+            .arg(Arg::with_name("COLOR")
+                .long("color")
+                .takes_value(true)
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+                .help("Color the diff printed by `--check` and `--diff`.{n}\
+                       With 'auto', the diff is colored only when stdout is a terminal."))
+            .arg(Arg::with_name("MESSAGE_FORMAT")
+                .long("message-format")
+                .takes_value(true)
+                .possible_values(&["human", "json"])
+                .default_value("human")
+                .help("Format of what is printed to stdout.{n}\
+                       With 'json', a JSON object like `{\"input\": \"...\", \"output\": \"...\", \
+                       \"template\": \"...\", \"bytes_written\": N}` describing the files used is \
+                       printed. With `--stdout`, the readme is not printed nor written anywhere. \
+                       Cannot be used with `--check` or `--diff`."))
+            .arg(Arg::with_name("LIST_TAGS")
+                .long("list-tags")
+                .help("Print the tags that can be used in a template, then exit.{n}\
+                       This does not need a Cargo project.")))
+        .get_matches_from(subcommand_args(env::args_os().collect()));
 
     if let Some(m) = matches.subcommand_matches("readme") {
-        match execute(m) {
-            Err(e) => {
-                io::stderr()
-                    .write_fmt(format_args!("Error: {}\n", e))
-                    .expect("An error occurred while trying to show an error message");
-                std::process::exit(1);
-            }
-            _ => {}
+        if let Err(e) = execute(m) {
+            // exit with an error even if the message cannot be shown, like when stderr is closed
+            let _ = writeln!(io::stderr(), "Error: {}", e);
+            std::process::exit(1);
         }
     }
 }
 
-/// Takes the arguments matches from clap and outputs the result, either to stdout of a file
+/// Make the `readme` subcommand implicit when the binary is run directly
+///
+/// Cargo runs `cargo-readme readme ...` for `cargo readme ...`, but running `cargo-readme ...`
+/// should work too, so `readme` is inserted when the first argument is something else.
+fn subcommand_args(mut args: Vec<OsString>) -> Vec<OsString> {
+    if args.len() < 2 || args[1] != "readme" {
+        let index = args.len().min(1);
+        args.insert(index, OsString::from("readme"));
+    }
+    args
+}
+
+/// Takes the arguments matches from clap and outputs the result, either to a file or to stdout
 fn execute(m: &ArgMatches) -> Result<(), String> {
+    if m.is_present("LIST_TAGS") {
+        print!("{}", helper::list_template_tags());
+        return Ok(());
+    }
+
     // get inputs
-    let input = m.value_of("INPUT");
+    let inputs = m.values_of("INPUT").map(|values| values.collect());
+    let input_glob = m.value_of("INPUT_GLOB");
+    let bin = m.value_of("BIN");
+    let prefer = match m.value_of("PREFER") {
+        Some("lib") => Some(Prefer::Lib),
+        Some("bin") => Some(Prefer::Bin),
+        _ => None,
+    };
     let output = m.value_of("OUTPUT");
+    let stdout = m.is_present("STDOUT");
+    let mkdir = m.is_present("MKDIR");
+    let if_changed = m.is_present("IF_CHANGED");
+    let force = m.is_present("FORCE");
     let template = m.value_of("TEMPLATE");
-    let add_title = !m.is_present("NO_TITLE");
-    let add_badges = !m.is_present("NO_BADGES");
-    let add_license = !m.is_present("NO_LICENSE");
+    let no_title = m.is_present("NO_TITLE");
+    let title_format = m
+        .value_of("TITLE_FORMAT")
+        .unwrap_or("# {name}\\n\\n")
+        .replace("\\n", "\n");
+    if !title_format.contains("{name}") {
+        return Err("Title format must contain `{name}`".to_owned());
+    }
+    let input_separator = m
+        .value_of("INPUT_SEPARATOR")
+        .unwrap_or("\\n")
+        .replace("\\n", "\n");
+    let no_license = m.is_present("NO_LICENSE");
+    // the default prefix is always present, so only a prefix given explicitly is an error
+    if m.occurrences_of("LICENSE_PREFIX") > 0 && m.is_present("LICENSE_PROSE") {
+        return Err("`--license-prefix` cannot be used with `--license-prose`".to_owned());
+    }
     let no_template = m.is_present("NO_TEMPLATE");
-    let indent_headings = !m.is_present("NO_INDENT_HEADINGS");
+    let heading_base_level = if m.is_present("NO_INDENT_HEADINGS") {
+        Some(0)
+    } else if m.is_present("HEADING_BASE_LEVEL") {
+        Some(
+            value_t!(m, "HEADING_BASE_LEVEL", usize)
+                .map_err(|_| "Heading base level must be a non-negative integer".to_owned())?,
+        )
+    } else {
+        None
+    };
+    let wrap = if m.is_present("WRAP") {
+        match value_t!(m, "WRAP", usize) {
+            Ok(width) if width > 0 => Some(width),
+            _ => return Err("Wrap width must be a positive integer".to_owned()),
+        }
+    } else {
+        None
+    };
+    let format = match m.value_of("FORMAT") {
+        Some("rst") => Format::Rst,
+        _ => Format::Markdown,
+    };
+    let check = m.is_present("CHECK");
+    let diff = m.is_present("DIFF");
+    let color = match m.value_of("COLOR") {
+        Some("always") => true,
+        Some("never") => false,
+        _ => atty::is(atty::Stream::Stdout),
+    };
+    // `{{date}}` is kept as it is by `--check` and `--diff` unless it is pinned, so the comparison
+    // does not depend on the day it runs
+    let date = match env::var("CARGO_README_DATE") {
+        Ok(date) => Some(date),
+        Err(_) if check || diff => None,
+        Err(_) => Some(helper::today()),
+    };
+    let json = m.value_of("MESSAGE_FORMAT") == Some("json");
+    if json && (check || diff) {
+        return Err("`--message-format json` cannot be used with `--check` or `--diff`".to_owned());
+    }
 
-    // get project root
-    let project_root = helper::get_project_root(m.value_of("ROOT"))?;
+    let options = ReadmeOptions::new()
+        .input_separator(&input_separator)
+        .title_format(&title_format)
+        .title_case(m.is_present("TITLE_CASE"))
+        .dedup_title(m.is_present("DEDUP_TITLE"))
+        .add_badges(!m.is_present("NO_BADGES"))
+        .license_prose(m.is_present("LICENSE_PROSE"))
+        .license_heading(m.is_present("LICENSE_HEADING"))
+        .license_body(m.is_present("LICENSE_BODY"))
+        .license_prefix(m.value_of("LICENSE_PREFIX").unwrap_or("License: "))
+        .license_link(m.is_present("LICENSE_LINK"))
+        .append_metadata(m.is_present("APPEND_METADATA"))
+        .strip_hidden(!m.is_present("NO_STRIP_HIDDEN"))
+        .fence_indented(m.is_present("FENCE_INDENTED"))
+        .rust_fence_label(m.value_of("RUST_FENCE_LABEL").unwrap_or("rust"))
+        .expand_intra_links(m.is_present("EXPAND_INTRA_LINKS"))
+        .rewrite_relative_links(m.is_present("REWRITE_RELATIVE_LINKS"))
+        .gfm_alerts(m.is_present("GFM_ALERTS"))
+        .strip_html_comments(m.is_present("STRIP_HTML_COMMENTS"))
+        .no_indent_headings(
+            m.values_of("NO_INDENT_HEADING")
+                .map(|values| values.map(String::from).collect())
+                .unwrap_or_default(),
+        )
+        .exclude_sections(
+            m.values_of("EXCLUDE_SECTION")
+                .map(|values| values.map(String::from).collect())
+                .unwrap_or_default(),
+        )
+        .add_anchors(m.is_present("ADD_ANCHORS"))
+        .squeeze_blank_lines(m.is_present("SQUEEZE_BLANK_LINES"))
+        .wrap(wrap)
+        .outer_docs(m.is_present("OUTER_DOCS"))
+        .include_modules(m.is_present("INCLUDE_MODULES"))
+        .allow_empty(m.is_present("ALLOW_EMPTY"))
+        .date(date)
+        .strict(m.is_present("STRICT"))
+        .format(format);
 
-    // get source file
-    let mut source = helper::get_source(&project_root, input)?;
+    if m.is_present("QUIET") {
+        log::set_verbosity(Verbosity::Quiet);
+    } else if m.is_present("VERBOSE") {
+        log::set_verbosity(Verbosity::Verbose);
+    }
 
-    // get destination file
-    let mut dest = helper::get_dest(&project_root, output)?;
+    // get project root; with `--input`, the docs can be read without `Cargo.toml`, as long as the
+    // crate information is not needed
+    let project_root = helper::get_project_root(m.value_of("ROOT"), inputs.is_none())?;
 
-    // get template file
-    let mut template_file = if no_template {
+    // `[package.metadata.readme]` provides the defaults for options not given
+    let manifest = if project_root.join("Cargo.toml").is_file() {
+        cargo_readme::get_manifest(&project_root)?
+    } else {
+        Manifest::default()
+    };
+    if manifest.readme_disabled {
+        log::notice(&format!(
+            "Skipping {}, `readme` is `false` in Cargo.toml",
+            manifest.name
+        ));
+        return Ok(());
+    }
+
+    // the input found by pattern or by binary name
+    let found_input = match (input_glob, bin) {
+        (Some(pattern), _) => Some(project::find_glob(&project_root, pattern)?),
+        (None, Some(bin)) => Some(project::find_bin_by_name(&project_root, &manifest, bin)?),
+        (None, None) => None,
+    };
+    let found_input = found_input.map(|path| path.to_string_lossy().into_owned());
+
+    let metadata = &manifest.readme;
+    let inputs = inputs
+        .or_else(|| found_input.as_ref().map(|input| vec![input.as_str()]))
+        .or_else(|| metadata.input.as_ref().map(|input| vec![input.as_str()]));
+    let output = output
+        .or(metadata.output.as_ref().map(String::as_str))
+        .or(manifest.readme_path.as_ref().map(String::as_str))
+        .or(if format == Format::Rst {
+            Some("README.rst")
+        } else {
+            None
+        });
+    // a template shared by the projects of a build environment, used if the project sets none
+    let env_template = env::var("CARGO_README_TEMPLATE")
+        .ok()
+        .filter(|template| !template.is_empty());
+    let template = template
+        .or(metadata.template.as_ref().map(String::as_str))
+        .or(env_template.as_ref().map(String::as_str));
+    let options = options
+        .add_title(metadata.add_title(no_title))
+        .add_license(metadata.add_license(no_license))
+        .heading_indent(metadata.heading_indent(heading_base_level));
+
+    // get source files
+    let (source_paths, mut sources): (Vec<_>, Vec<_>) =
+        helper::get_sources(&project_root, inputs, prefer)?
+            .into_iter()
+            .unzip();
+
+    // get destination file, unless we are only comparing with it or printing to stdout; it is
+    // only written once the readme is generated, so it is left untouched on errors
+    let dest = if check || diff || stdout {
         None
     } else {
-        helper::get_template_file(&project_root, template)?
+        Some(helper::get_dest(&project_root, output, mkdir)?)
     };
 
+    // get template file
+    let (template_path, mut template_file) = if no_template {
+        (None, None)
+    } else {
+        match helper::get_template_file(&project_root, template)? {
+            Some((path, file)) => (Some(path), Some(file)),
+            None => (None, None),
+        }
+    };
+
+    if let Some(ref dest) = dest {
+        if if_changed
+            && helper::is_up_to_date(
+                dest,
+                &source_paths,
+                template_path.as_ref().map(AsRef::as_ref),
+            )
+        {
+            log::notice(&format!(
+                "Skipping {}, it is newer than the inputs",
+                project::display_path(dest)
+            ));
+            if json {
+                let report = Report {
+                    inputs: source_paths,
+                    output: None,
+                    template: template_path,
+                    bytes_written: 0,
+                };
+                println!("{}", report.to_json());
+            }
+            return Ok(());
+        }
+    }
+
     // generate output
+    let options = options.source_paths(source_paths.clone());
     let readme = cargo_readme::generate_readme(
         &project_root,
-        &mut source,
-        template_file.as_mut(),
-        add_title,
-        add_badges,
-        add_license,
-        indent_headings,
+        &mut sources,
+        template_file
+            .as_mut()
+            .map(|template| template as &mut dyn Read),
+        &options,
     )?;
 
-    helper::write_output(&mut dest, readme)
+    if check {
+        return helper::check_output(&project_root, output, &readme, color);
+    }
+
+    if diff {
+        return helper::diff_output(&project_root, output, &readme, color);
+    }
+
+    if json {
+        let bytes_written = match dest {
+            Some(ref dest) => {
+                let bytes = readme.len();
+                helper::write_output(Some(dest), readme, force)?;
+                bytes
+            }
+            None => 0,
+        };
+        let report = Report {
+            inputs: source_paths,
+            output: dest,
+            template: template_path,
+            bytes_written,
+        };
+        println!("{}", report.to_json());
+        return Ok(());
+    }
+
+    helper::write_output(dest.as_ref().map(AsRef::as_ref), readme, force)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use super::subcommand_args;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn subcommand_args_from_cargo() {
+        assert_eq!(
+            subcommand_args(args(&["cargo-readme", "readme", "-i", "src/lib.rs"])),
+            args(&["cargo-readme", "readme", "-i", "src/lib.rs"])
+        );
+    }
+
+    #[test]
+    fn subcommand_args_standalone() {
+        assert_eq!(
+            subcommand_args(args(&["cargo-readme", "-i", "src/lib.rs"])),
+            args(&["cargo-readme", "readme", "-i", "src/lib.rs"])
+        );
+        assert_eq!(
+            subcommand_args(args(&["cargo-readme"])),
+            args(&["cargo-readme", "readme"])
+        );
+    }
 }
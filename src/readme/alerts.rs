@@ -0,0 +1,96 @@
+//! Rewrite the "Note:" and "Warning:" blockquotes of rustdoc into GitHub alerts
+//!
+//! A blockquote starting with a known keyword, like `> Note: text` or `> **Warning:** text`,
+//! becomes an alert, like `> [!NOTE]` followed by `> text`. Other blockquotes and code blocks are
+//! left untouched.
+
+use regex::Regex;
+
+use super::fence::FenceTracker;
+
+lazy_static! {
+    // First line of a blockquote starting with a keyword, like "> Note: text" or "> **Note**: text"
+    static ref RE_ALERT: Regex = Regex::new(r"(?i)^(?P<quote> {0,3}> ?)(?:\*\*(?P<bold>note|tip|important|warning|caution)(?::\*\*|\*\*:)|(?P<plain>note|tip|important|warning|caution):)(?:\s+(?P<text>.*))?$").unwrap();
+}
+
+/// Rewrite the blockquotes starting with a known keyword into GitHub alerts
+pub fn rewrite_alerts(lines: Vec<String>) -> Vec<String> {
+    let mut fence = FenceTracker::new();
+    let mut in_quote = false;
+    let mut result = Vec::with_capacity(lines.len());
+
+    for line in lines {
+        if fence.is_code(&line) {
+            in_quote = false;
+            result.push(line);
+            continue;
+        }
+
+        let starts_quote = !in_quote;
+        in_quote = line.trim_start().starts_with('>');
+
+        if starts_quote {
+            if let Some(cap) = RE_ALERT.captures(&line) {
+                let keyword = cap
+                    .name("bold")
+                    .or_else(|| cap.name("plain"))
+                    .map_or("", |keyword| keyword.as_str());
+                result.push(format!("{}[!{}]", &cap["quote"], keyword.to_uppercase()));
+                if let Some(text) = cap.name("text") {
+                    result.push(format!("{}{}", &cap["quote"], text.as_str()));
+                }
+                continue;
+            }
+        }
+
+        result.push(line);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rewrite_alerts;
+
+    fn rewrite(lines: &[&str]) -> Vec<String> {
+        rewrite_alerts(lines.iter().map(|line| line.to_string()).collect())
+    }
+
+    #[test]
+    fn rewrite_note() {
+        assert_eq!(
+            rewrite(&["> Note: this is important", "> to know"]),
+            &["> [!NOTE]", "> this is important", "> to know"]
+        );
+        assert_eq!(
+            rewrite(&["> **Note:** bold", "", "> **note**: lowercase"]),
+            &["> [!NOTE]", "> bold", "", "> [!NOTE]", "> lowercase"]
+        );
+    }
+
+    #[test]
+    fn rewrite_warning() {
+        assert_eq!(
+            rewrite(&["text", "", ">Warning:", "> this can fail"]),
+            &["text", "", ">[!WARNING]", "> this can fail"]
+        );
+    }
+
+    #[test]
+    fn keep_other_blockquotes() {
+        let lines = &[
+            "> Notes: not a keyword",
+            "",
+            "> Quoted text",
+            "> Note: not the start of the blockquote",
+            "",
+            "Note: not a blockquote",
+            "",
+            "```text",
+            "> Note: in a code block",
+            "```",
+        ];
+        assert_eq!(rewrite(lines), lines);
+    }
+}
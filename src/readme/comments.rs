@@ -0,0 +1,143 @@
+//! Strip the HTML comments from the docs, like `<!-- note for the maintainers -->`
+//!
+//! A comment can be inline or span several lines. The lines left blank by a removed comment are
+//! dropped, as well as the blank line following them if they were after a blank line, and
+//! comments in code blocks are left untouched.
+
+use super::fence::FenceTracker;
+
+const COMMENT_START: &str = "<!--";
+const COMMENT_END: &str = "-->";
+
+/// Remove the HTML comments outside of code blocks
+pub fn strip_html_comments(lines: Vec<String>) -> Vec<String> {
+    let mut fence = FenceTracker::new();
+    let mut in_comment = false;
+    // whether the last line was dropped because it was only a comment
+    let mut dropped = false;
+    let mut result = Vec::with_capacity(lines.len());
+
+    for line in lines {
+        // a fence inside a comment is part of the comment
+        if !in_comment && fence.is_code(&line) {
+            result.push(line);
+            dropped = false;
+            continue;
+        }
+
+        let (stripped, still_in_comment) = strip_line(&line, in_comment);
+        let changed = in_comment || still_in_comment || stripped.len() != line.len();
+        in_comment = still_in_comment;
+
+        if !changed {
+            // a comment between two blank lines leaves a single one
+            let after_blank = result
+                .last()
+                .is_none_or(|last: &String| last.trim().is_empty());
+            if !(dropped && after_blank && line.trim().is_empty()) {
+                result.push(line);
+            }
+            dropped = false;
+        } else if !stripped.trim().is_empty() {
+            result.push(stripped.trim_end().to_owned());
+            dropped = false;
+        } else {
+            dropped = true;
+        }
+    }
+
+    result
+}
+
+/// Remove the comments from a line, `in_comment` telling if the line starts inside a comment
+///
+/// Returns the rest of the line and whether it ends inside a comment.
+fn strip_line(line: &str, mut in_comment: bool) -> (String, bool) {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+
+    loop {
+        if in_comment {
+            match rest.find(COMMENT_END) {
+                Some(end) => {
+                    rest = &rest[end + COMMENT_END.len()..];
+                    in_comment = false;
+                }
+                None => return (result, true),
+            }
+        } else {
+            match rest.find(COMMENT_START) {
+                Some(start) => {
+                    result.push_str(&rest[..start]);
+                    rest = &rest[start + COMMENT_START.len()..];
+                    in_comment = true;
+                }
+                None => {
+                    result.push_str(rest);
+                    return (result, false);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_html_comments;
+
+    fn strip(lines: &[&str]) -> Vec<String> {
+        strip_html_comments(lines.iter().map(|line| line.to_string()).collect())
+    }
+
+    #[test]
+    fn strip_inline_comments() {
+        assert_eq!(
+            strip(&[
+                "Some <!-- hidden --> text",
+                "<!-- only a comment -->",
+                "Text <!-- one --> and <!-- two -->",
+            ]),
+            &["Some  text", "Text  and"]
+        );
+    }
+
+    #[test]
+    fn strip_multiline_comments() {
+        assert_eq!(
+            strip(&[
+                "First",
+                "<!--",
+                "A comment",
+                "```",
+                "with a fence",
+                "-->",
+                "Second <!-- starts here",
+                "and ends here --> third",
+            ]),
+            &["First", "Second", " third"]
+        );
+        assert_eq!(
+            strip(&["First", "", "<!--", "comment", "-->", "", "Second"]),
+            &["First", "", "Second"]
+        );
+    }
+
+    #[test]
+    fn keep_comments_in_code_blocks() {
+        let lines = &[
+            "```html",
+            "<!-- in a code block -->",
+            "<!--",
+            "```",
+            "",
+            "Text",
+        ];
+        assert_eq!(strip(lines), lines);
+    }
+
+    #[test]
+    fn keep_lines_without_comments() {
+        let lines = &["Text  ", "", "Trailing spaces are a line break"];
+        assert_eq!(strip(lines), lines);
+    }
+}
@@ -1,21 +1,106 @@
 //! Extract raw doc comments from rust source code
 
-use std::io::{self, BufRead, BufReader, Read};
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::iter;
+
+use regex::Regex;
+
+use super::fence::FenceTracker;
+use super::headings::parse_heading;
+
+// Doc line marking where the docs used for the readme start
+const START_MARKER: &str = "<!-- readme-start -->";
+// Doc line marking where the docs used for the readme stop
+const STOP_MARKER: &str = "<!-- readme-stop -->";
+// Byte order mark that some editors write at the start of UTF-8 files
+const BOM: char = '\u{feff}';
+
+lazy_static! {
+    // Module declared inline, like "mod util {" or "pub(crate) mod util {"
+    static ref RE_INLINE_MODULE: Regex = Regex::new(r"(?m)^\s*(?:pub(?:\s*\([^)]*\))?\s+)?mod\s+(?P<name>[A-Za-z_][A-Za-z0-9_]*)\s*\{").unwrap();
+}
 
 /// Read the given `Read`er and return a `Vec` of the rustdoc lines found
-pub fn extract_docs<R: Read>(reader: R) -> io::Result<Vec<String>> {
+///
+/// If `outer_docs` is set, `///` item docs are also read, for files documenting a single item.
+///
+/// The docs included with `#![doc = include_str!("path")]` are read with `include`, which gets
+/// the path as written in the source.
+///
+/// If the docs have a `<!-- readme-start -->` or a `<!-- readme-stop -->` line, only the lines
+/// after the first and before the second are returned.
+///
+/// A byte order mark and a shebang line, like `#!/usr/bin/env run-cargo-script`, at the start of
+/// the source are skipped.
+///
+/// If `include_modules` is set, the `//!` docs of the modules declared inline, like
+/// `mod util { ... }`, are appended after the crate docs, each under a heading with the module
+/// name. See `extract_module_docs`.
+pub fn extract_docs<R: Read>(
+    mut reader: R,
+    outer_docs: bool,
+    include_modules: bool,
+    include: &dyn Fn(&str) -> io::Result<String>,
+) -> io::Result<Vec<String>> {
+    if include_modules {
+        let mut source = String::new();
+        reader.read_to_string(&mut source)?;
+
+        // the crate docs are before any item, so the docs of the modules are not mistaken for them
+        let crate_docs_end = RE_INLINE_MODULE
+            .find(&source)
+            .map_or(source.len(), |module| module.start());
+        let mut result = extract_docs(
+            Cursor::new(&source[..crate_docs_end]),
+            outer_docs,
+            false,
+            include,
+        )?;
+
+        let module_docs = extract_module_docs(&source, include)?;
+        if !result.is_empty() && !module_docs.is_empty() {
+            result.push(String::new());
+        }
+        result.extend(module_docs);
+
+        return Ok(result);
+    }
+
     let mut reader = BufReader::new(reader);
     let mut line = String::new();
+    // lines in strings or block comments are never docs, even if they look like it
+    let mut code = CodeScanner::new();
+    let mut first_line = true;
 
     while reader.read_line(&mut line)? > 0 {
-        if line.starts_with("//!") {
-            return extract_docs_singleline_style(line, reader);
+        trim_line_ending(&mut line);
+
+        if first_line {
+            first_line = false;
+            if line.starts_with(BOM) {
+                line.drain(..BOM.len_utf8());
+            }
+            if is_shebang(&line) {
+                line.clear();
+                continue;
+            }
         }
 
-        if line.starts_with("/*!") {
-            return extract_docs_multiline_style(line, reader);
+        if code.in_code() {
+            if is_doc_comment(&line, outer_docs)
+                || parse_doc_attribute(&line).is_some()
+                || parse_include_attribute(&line).is_some()
+            {
+                return extract_docs_singleline_style(line, reader, outer_docs, include)
+                    .map(select_marked_lines);
+            }
+
+            if line.starts_with("/*!") {
+                return extract_docs_multiline_style(line, reader).map(select_marked_lines);
+            }
         }
 
+        code.scan(&line);
         line.clear();
     }
 
@@ -25,72 +110,520 @@ pub fn extract_docs<R: Read>(reader: R) -> io::Result<Vec<String>> {
 fn extract_docs_singleline_style<R: Read>(
     first_line: String,
     reader: BufReader<R>,
+    outer_docs: bool,
+    include: &dyn Fn(&str) -> io::Result<String>,
 ) -> io::Result<Vec<String>> {
-    let mut result = vec![normalize_line(first_line)];
+    let mut result: Vec<String> = Vec::new();
+    // non doc lines, like inner attributes or regular comments, were found since the last doc line
+    let mut gap = false;
 
-    for line in reader.lines() {
-        let line = line?;
+    for line in iter::once(Ok(first_line)).chain(reader.lines()) {
+        let mut line = line?;
+        trim_line_ending(&mut line);
 
-        if line.starts_with("//!") {
-            result.push(normalize_line(line));
-        } else if line.trim().len() > 0 {
+        let doc_lines = if let Some(lines) = parse_doc_line(&line, outer_docs, include)? {
+            lines
+        } else if is_skipped_line(&line) {
+            gap = true;
+            continue;
+        } else {
             // doc ends, code starts
             break;
+        };
+
+        // separate doc blocks, so they are not merged into the same paragraph
+        if gap && result.last().is_some_and(|last| !last.is_empty()) {
+            result.push(String::new());
+        }
+        gap = false;
+
+        result.extend(doc_lines);
+    }
+
+    Ok(result)
+}
+
+/// Get the docs of a doc comment or doc attribute line, or `None` if it is not a doc line
+fn parse_doc_line(
+    line: &str,
+    outer_docs: bool,
+    include: &dyn Fn(&str) -> io::Result<String>,
+) -> io::Result<Option<Vec<String>>> {
+    if is_doc_comment(line, outer_docs) {
+        Ok(Some(vec![normalize_line(line.to_owned())]))
+    } else if let Some(lines) = parse_doc_attribute(line) {
+        Ok(Some(lines))
+    } else if let Some(path) = parse_include_attribute(line) {
+        Ok(Some(
+            include(&path)?
+                .lines()
+                .map(|l| l.trim_end().to_owned())
+                .collect(),
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Extract the `//!` docs of the modules declared inline, like `mod util { ... }`
+///
+/// The docs of each module are preceded by a heading with its name, one level deeper for each
+/// nested module, and the headings of the docs themselves are moved below it. Modules without
+/// docs are skipped. The end of the modules is found by counting the braces of the code, outside
+/// of strings and comments.
+fn extract_module_docs(
+    source: &str,
+    include: &dyn Fn(&str) -> io::Result<String>,
+) -> io::Result<Vec<String>> {
+    let mut result: Vec<String> = Vec::new();
+    // brace depth of the code where each of the modules containing the current line starts
+    let mut modules: Vec<isize> = Vec::new();
+    let mut depth: isize = 0;
+    // docs of the module whose inner docs are being read, with its name
+    let mut current: Option<(String, Vec<String>)> = None;
+    let mut gap = false;
+    let mut code = CodeScanner::new();
+
+    for line in source.lines() {
+        if !code.in_code() {
+            depth += code.scan(line);
+            continue;
+        }
+
+        if let Some((name, mut docs)) = current.take() {
+            if let Some(lines) = parse_doc_line(line, false, include)? {
+                if gap && docs.last().is_some_and(|last| !last.is_empty()) {
+                    docs.push(String::new());
+                }
+                gap = false;
+                docs.extend(lines);
+                current = Some((name, docs));
+                continue;
+            } else if is_skipped_line(line) {
+                gap = true;
+                current = Some((name, docs));
+                continue;
+            }
+
+            push_module_docs(&mut result, &name, modules.len(), docs);
         }
+
+        if is_doc_comment(line, true) {
+            continue;
+        }
+
+        if let Some(cap) = RE_INLINE_MODULE.captures(line) {
+            modules.push(depth);
+            current = Some((cap["name"].to_owned(), Vec::new()));
+            gap = false;
+        }
+
+        depth += code.scan(line);
+        while modules.last().is_some_and(|&start| depth <= start) {
+            modules.pop();
+        }
+    }
+
+    if let Some((name, docs)) = current {
+        push_module_docs(&mut result, &name, modules.len(), docs);
     }
 
     Ok(result)
 }
 
+/// Append the docs of a module under a heading of the given level
+fn push_module_docs(result: &mut Vec<String>, name: &str, level: usize, docs: Vec<String>) {
+    if docs.iter().all(|line| line.trim().is_empty()) {
+        return;
+    }
+
+    if !result.is_empty() {
+        result.push(String::new());
+    }
+    result.push(format!("{} {}", "#".repeat(level), name));
+    result.push(String::new());
+
+    let mut fence = FenceTracker::new();
+    result.extend(docs.into_iter().map(|line| {
+        if fence.is_code(&line) {
+            return line;
+        }
+        match parse_heading(&line) {
+            Some((heading_level, text)) => {
+                format!("{} {}", "#".repeat((heading_level + level).min(6)), text)
+            }
+            None => line,
+        }
+    }));
+}
+
+/// Where a line of code ends: in code, or inside a string or a block comment
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CodeState {
+    Code,
+    String,
+    /// Raw string, with the number of `#` closing it
+    RawString(usize),
+    /// Block comment, with its nesting level
+    BlockComment(usize),
+}
+
+/// Follow the strings and comments of code read line by line
+///
+/// A line starting inside a string or a comment, like a line of a multiline string literal that
+/// looks like `//! docs`, is not code and must not be read as a doc comment.
+struct CodeScanner {
+    state: CodeState,
+}
+
+impl CodeScanner {
+    fn new() -> Self {
+        CodeScanner {
+            state: CodeState::Code,
+        }
+    }
+
+    /// Does the next line start in code, outside of strings and block comments?
+    fn in_code(&self) -> bool {
+        self.state == CodeState::Code
+    }
+
+    /// Read a line and return the difference between its opening and closing braces
+    ///
+    /// Braces in comments, strings and char literals are ignored.
+    fn scan(&mut self, line: &str) -> isize {
+        let chars: Vec<char> = line.chars().collect();
+        let next = |i: usize| chars.get(i + 1).cloned();
+        let mut balance = 0;
+        let mut i = 0;
+
+        while i < chars.len() {
+            match self.state {
+                CodeState::String => match chars[i] {
+                    '\\' => i += 1,
+                    '"' => self.state = CodeState::Code,
+                    _ => {}
+                },
+                CodeState::RawString(hashes) => {
+                    if chars[i] == '"'
+                        && chars[i + 1..].iter().take_while(|&&c| c == '#').count() >= hashes
+                    {
+                        self.state = CodeState::Code;
+                        i += hashes;
+                    }
+                }
+                CodeState::BlockComment(level) => {
+                    if chars[i] == '*' && next(i) == Some('/') {
+                        self.state = if level > 1 {
+                            CodeState::BlockComment(level - 1)
+                        } else {
+                            CodeState::Code
+                        };
+                        i += 1;
+                    } else if chars[i] == '/' && next(i) == Some('*') {
+                        self.state = CodeState::BlockComment(level + 1);
+                        i += 1;
+                    }
+                }
+                CodeState::Code => match chars[i] {
+                    '/' if next(i) == Some('/') => break,
+                    '/' if next(i) == Some('*') => {
+                        self.state = CodeState::BlockComment(1);
+                        i += 1;
+                    }
+                    '"' => self.state = CodeState::String,
+                    'r' if is_raw_string_start(&chars, i) => {
+                        let hashes = chars[i + 1..].iter().take_while(|&&c| c == '#').count();
+                        self.state = CodeState::RawString(hashes);
+                        i += hashes + 1;
+                    }
+                    '\'' => {
+                        // char literals like '{' or '\'', lifetimes have no closing quote
+                        if next(i) == Some('\\') {
+                            i += 3;
+                        } else if chars.get(i + 2) == Some(&'\'') {
+                            i += 2;
+                        }
+                    }
+                    '{' => balance += 1,
+                    '}' => balance -= 1,
+                    _ => {}
+                },
+            }
+            i += 1;
+        }
+
+        balance
+    }
+}
+
+/// Is the `r` at `index` the start of a raw string, like `r"..."`, `r#"..."#` or `br"..."`?
+fn is_raw_string_start(chars: &[char], index: usize) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let prefix_ok = match index.checked_sub(1).map(|i| chars[i]) {
+        None => true,
+        Some('b') => index < 2 || !is_ident(chars[index - 2]),
+        Some(c) => !is_ident(c),
+    };
+    let hashes = chars[index + 1..].iter().take_while(|&&c| c == '#').count();
+
+    prefix_ok && chars.get(index + 1 + hashes) == Some(&'"')
+}
+
 fn extract_docs_multiline_style<R: Read>(
     first_line: String,
     reader: BufReader<R>,
 ) -> io::Result<Vec<String>> {
     let mut result = Vec::new();
-    if first_line.starts_with("/*!") && first_line.trim().len() > "/*!".len() {
-        result.push(normalize_line(first_line));
-    }
+
+    // text can follow the "/*!" mark, and the comment can even be closed on the same line
+    let first_line = normalize_line(first_line);
+    let has_first_line = !first_line.is_empty();
+    let lines = iter::once(Ok(first_line)).chain(reader.lines());
 
     let mut nesting: isize = 0;
 
-    for line in reader.lines() {
-        let line = line?;
+    for (index, line) in lines.enumerate() {
+        let mut line = line?;
+        trim_line_ending(&mut line);
         nesting += line.matches("/*").count() as isize;
 
         if let Some(pos) = line.rfind("*/") {
             nesting -= line.matches("*/").count() as isize;
             if nesting < 0 {
-                let mut line = line;
-                line.split_off(pos);
+                line.truncate(pos);
                 if !line.trim().is_empty() {
-                    result.push(line);
+                    result.push(line.trim_end().to_owned());
                 }
                 break;
             }
         }
 
-        result.push(line.trim_end().to_owned());
+        if index > 0 || has_first_line {
+            result.push(line.trim_end().to_owned());
+        }
     }
 
+    // the text on the same line as "/*!" is never decorated with a leading "*"
+    let skip = if has_first_line { 1 } else { 0 };
+    strip_leading_asterisks(&mut result[skip..]);
+
     Ok(result)
 }
 
-/// Strip the "//!" or "/*!" from a line and a single whitespace
-fn normalize_line(mut line: String) -> String {
-    if line.trim() == "//!" || line.trim() == "/*!" {
-        line.clear();
-        line
-    } else {
-        // if the first character after the comment mark is " ", remove it
-        let split_at = if line.find(" ") == Some(3) { 4 } else { 3 };
-        line.split_at(split_at).1.trim_end().to_owned()
+/// Keep the lines between the start and the stop markers, without the markers themselves
+///
+/// A missing start marker means the start of the docs, and a missing stop marker the end. The
+/// markers in code blocks are part of the code, so they are ignored.
+fn select_marked_lines(mut lines: Vec<String>) -> Vec<String> {
+    let mut fence = FenceTracker::new();
+    let mut start = None;
+    let mut stop = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        if fence.is_code(line) {
+            continue;
+        }
+
+        match line.trim() {
+            START_MARKER if start.is_none() => start = Some(index),
+            STOP_MARKER => {
+                stop = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    lines.truncate(stop.unwrap_or(lines.len()));
+    if let Some(start) = start {
+        lines.drain(..start + 1);
+    }
+
+    lines
+}
+
+/// Lines starting with `//!`, or also with `///` if `outer_docs` is set
+///
+/// Like in rust, lines starting with `////` are regular comments. The comment can be indented,
+/// like the docs of a module declared inline.
+fn is_doc_comment(line: &str, outer_docs: bool) -> bool {
+    let line = line.trim_start();
+    line.starts_with("//!") || (outer_docs && line.starts_with("///") && !line.starts_with("////"))
+}
+
+/// Lines that can be found between doc comments: blank lines, regular comments and inner
+/// attributes, like `#![allow(dead_code)]`
+fn is_skipped_line(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty()
+        || (line.starts_with("//") && !line.starts_with("//!"))
+        || line.starts_with("#![")
+}
+
+/// First line of a script, like `#!/usr/bin/env run-cargo-script`, which is not rust code
+///
+/// Like in rust, a line starting with `#![` is an inner attribute and not a shebang.
+fn is_shebang(line: &str) -> bool {
+    line.starts_with("#!") && !line[2..].trim_start().starts_with('[')
+}
+
+/// Remove the line ending, including the "\r" that `lines()` leaves on files with CRLF endings
+fn trim_line_ending(line: &mut String) {
+    if line.ends_with('\n') {
+        line.pop();
+    }
+    if line.ends_with('\r') {
+        line.pop();
     }
 }
 
+/// Remove the leading " * " decoration of multiline doc comments
+///
+/// Like rustdoc, the decoration is only removed if every non blank line has it, so markdown
+/// lists are not mistaken for it.
+fn strip_leading_asterisks(lines: &mut [String]) {
+    let decorated = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .all(|line| line.trim_start().starts_with('*'));
+
+    if !decorated {
+        return;
+    }
+
+    for line in lines.iter_mut() {
+        let stripped = {
+            let rest = line.trim_start();
+            let rest = rest.strip_prefix('*').unwrap_or(rest);
+            rest.strip_prefix(' ').unwrap_or(rest).to_owned()
+        };
+        *line = stripped;
+    }
+}
+
+/// Parse a `#![doc = "..."]` attribute into the lines of its unescaped string
+///
+/// Returns `None` if the line is not a doc attribute with a single string literal.
+fn parse_doc_attribute(line: &str) -> Option<Vec<String>> {
+    let text = unescape_string_literal(doc_attribute_value(line)?)?;
+
+    Some(text.split('\n').map(|l| l.trim_end().to_owned()).collect())
+}
+
+/// Parse a `#![doc = include_str!("...")]` attribute into the path of the included file
+fn parse_include_attribute(line: &str) -> Option<String> {
+    let value = doc_attribute_value(line)?;
+    if !value.starts_with("include_str!") {
+        return None;
+    }
+
+    let args = value["include_str!".len()..].trim();
+    if !args.starts_with('(') || !args.ends_with(')') {
+        return None;
+    }
+
+    unescape_string_literal(args[1..args.len() - 1].trim())
+}
+
+/// Get the paths of the files included in the docs of a source with `#![doc = include_str!("...")]`
+pub fn included_paths(source: &str) -> Vec<String> {
+    source.lines().filter_map(parse_include_attribute).collect()
+}
+
+/// Get the value of a `#![doc = ...]` attribute, the expression after the `=`
+fn doc_attribute_value(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if !line.starts_with("#![doc") || !line.ends_with(']') {
+        return None;
+    }
+
+    let value = line["#![doc".len()..line.len() - 1].trim();
+    if !value.starts_with('=') {
+        return None;
+    }
+
+    Some(value[1..].trim())
+}
+
+/// Get the contents of a rust string literal, either regular or raw
+fn unescape_string_literal(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.chars().take_while(|&c| c == '#').count();
+        let closing = format!("\"{}", "#".repeat(hashes));
+        let quoted = &raw[hashes..];
+        if !quoted.starts_with('"') || !quoted.ends_with(&closing) || quoted.len() <= hashes + 1 {
+            return None;
+        }
+        return Some(quoted[1..quoted.len() - closing.len()].to_owned());
+    }
+
+    if !literal.starts_with('"') || !literal.ends_with('"') || literal.len() < 2 {
+        return None;
+    }
+
+    let mut result = String::new();
+    let mut chars = literal[1..literal.len() - 1].chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => result.push('\n'),
+                'r' => result.push('\r'),
+                't' => result.push('\t'),
+                '0' => result.push('\0'),
+                '\\' => result.push('\\'),
+                '"' => result.push('"'),
+                '\'' => result.push('\''),
+                'x' => {
+                    let code: String = chars.by_ref().take(2).collect();
+                    result.push(u8::from_str_radix(&code, 16).ok()? as char);
+                }
+                'u' => {
+                    if chars.next()? != '{' {
+                        return None;
+                    }
+                    let code: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    let code = u32::from_str_radix(&code.replace("_", ""), 16).ok()?;
+                    result.push(::std::char::from_u32(code)?);
+                }
+                _ => return None,
+            },
+            // an unescaped quote means this is not a single string literal
+            '"' => return None,
+            c => result.push(c),
+        }
+    }
+
+    Some(result)
+}
+
+/// Strip the indentation, the "//!", "///" or "/*!" from a line and a single whitespace
+///
+/// The text can follow the mark without a space, like in `//!text`, and is then kept as is, so
+/// the indentation inside code blocks is preserved.
+fn normalize_line(line: String) -> String {
+    let line = line.trim_start();
+    let text = ["//!", "///", "/*!"]
+        .iter()
+        .filter_map(|mark| line.strip_prefix(mark))
+        .next()
+        .unwrap_or(line);
+    let text = text.strip_prefix(' ').unwrap_or(text);
+    text.trim_end().to_owned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Cursor;
+    use std::io::{Cursor, ErrorKind};
+
+    fn no_include(path: &str) -> io::Result<String> {
+        Err(io::Error::new(
+            ErrorKind::NotFound,
+            format!("unexpected include of {}", path),
+        ))
+    }
 
     const EXPECTED: &[&str] = &[
         "first line",
@@ -120,7 +653,7 @@ mod tests {
     #[test]
     fn extract_docs_singleline_style() {
         let reader = Cursor::new(INPUT_SINGLELINE.as_bytes());
-        let result = extract_docs(reader).unwrap();
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
         assert_eq!(result, EXPECTED);
     }
 
@@ -142,7 +675,177 @@ mod tests {
     #[test]
     fn extract_docs_multiline_style() {
         let reader = Cursor::new(INPUT_MULTILINE.as_bytes());
-        let result = extract_docs(reader).unwrap();
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, EXPECTED);
+    }
+
+    #[test]
+    fn extract_docs_singleline_style_crlf() {
+        let input = INPUT_SINGLELINE.replace(" \n", "\r\n");
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, EXPECTED);
+        assert!(result.iter().all(|line| !line.contains('\r')));
+    }
+
+    #[test]
+    fn extract_docs_multiline_style_crlf() {
+        let input = INPUT_MULTILINE.replace(" \n", "\r\n");
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, EXPECTED);
+        assert!(result.iter().all(|line| !line.contains('\r')));
+    }
+
+    #[test]
+    fn extract_docs_doc_attribute_crlf() {
+        let input =
+            "#![doc = \"first line\"]\r\n#![doc = \"\"]\r\n#![doc = \"```\"]\r\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["first line", "", "```"]);
+    }
+
+    #[test]
+    fn extract_docs_with_bom() {
+        let input = format!("\u{feff}{}", INPUT_SINGLELINE);
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, EXPECTED);
+
+        let input = format!("\u{feff}{}", INPUT_MULTILINE);
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, EXPECTED);
+    }
+
+    #[test]
+    fn extract_docs_with_shebang() {
+        let input = format!("#!/usr/bin/env run-cargo-script\n{}", INPUT_SINGLELINE);
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, EXPECTED);
+
+        // a quote in the shebang does not start a string hiding the docs
+        let input = "#!/usr/bin/env -S sh -c 'cargo run'\n//! docs\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["docs"]);
+
+        let input = "\u{feff}#!/usr/bin/env run-cargo-script\n//! docs\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["docs"]);
+    }
+
+    #[test]
+    fn extract_docs_attribute_is_not_shebang() {
+        let input = "#![doc = \"first line\"]\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["first line"]);
+    }
+
+    const INPUT_SEPARATED_BLOCKS: &str = "\
+                                          //! first block\n\
+                                          //! still first block\n\
+                                          #![allow(dead_code)]\n\
+                                          // regular comment\n\
+                                          //! second block\n\
+                                          //!\n\
+                                          \n\
+                                          //! third block\n\
+                                          use std::any::Any;\n\
+                                          //! not a doc comment of the crate";
+
+    #[test]
+    fn extract_docs_separated_blocks() {
+        let reader = Cursor::new(INPUT_SEPARATED_BLOCKS.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(
+            result,
+            &[
+                "first block",
+                "still first block",
+                "",
+                "second block",
+                "",
+                "third block",
+            ]
+        );
+    }
+
+    const INPUT_MULTILINE_DECORATED: &[&str] = &[
+        "/*!",
+        " * first line",
+        " *",
+        " * ```",
+        " * let rust_code = \"safe\";",
+        " * ```",
+        " *",
+        " * ```C",
+        " * int i = 0; // no rust code",
+        " * ```",
+        " */",
+        "use std::any::Any;",
+        "fn main() {}",
+    ];
+
+    #[test]
+    fn extract_docs_multiline_style_decorated() {
+        let input = INPUT_MULTILINE_DECORATED.join("\n");
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, EXPECTED);
+    }
+
+    const INPUT_MULTILINE_LIST: &[&str] = &["/*!", "list:", "", "* item 1", "* item 2", "*/"];
+
+    const EXPECTED_MULTILINE_LIST: &[&str] = &["list:", "", "* item 1", "* item 2"];
+
+    #[test]
+    fn extract_docs_multiline_style_keep_list() {
+        let input = INPUT_MULTILINE_LIST.join("\n");
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, EXPECTED_MULTILINE_LIST);
+    }
+
+    const INPUT_MULTILINE_SINGLE_LINE: &str = "\
+                                               /*! single line */ \n\
+                                               fn main() {}";
+
+    #[test]
+    fn extract_docs_multiline_style_single_line() {
+        let reader = Cursor::new(INPUT_MULTILINE_SINGLE_LINE.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["single line"]);
+    }
+
+    const INPUT_DOC_ATTRIBUTE: &str = "\
+                                       #![doc = \"first line\"] \n\
+                                       #![doc = \"\"] \n\
+                                       #![doc = \"with \\\"quotes\\\"\"] \n\
+                                       fn main() {}";
+
+    #[test]
+    fn extract_docs_doc_attribute() {
+        let reader = Cursor::new(INPUT_DOC_ATTRIBUTE.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["first line", "", "with \"quotes\""]);
+    }
+
+    const INPUT_DOC_ATTRIBUTE_NEWLINES: &str = r#"#![doc = "first line\n\n```\nlet rust_code = \"safe\";\n```"]
+//!
+#![doc = r"```C"]
+//! int i = 0; // no rust code
+//! ```
+fn main() {}"#;
+
+    #[test]
+    fn extract_docs_doc_attribute_with_newlines() {
+        let reader = Cursor::new(INPUT_DOC_ATTRIBUTE_NEWLINES.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
         assert_eq!(result, EXPECTED);
     }
 
@@ -156,7 +859,7 @@ mod tests {
     fn extract_docs_mix_styles_singleline() {
         let input = Cursor::new(INPUT_MIXED_SINGLELINE.as_bytes());
         let expected = "singleline";
-        let result = extract_docs(input).unwrap();
+        let result = extract_docs(input, false, false, &no_include).unwrap();
         assert_eq!(result, &[expected])
     }
 
@@ -170,7 +873,7 @@ mod tests {
     fn extract_docs_mix_styles_multiline() {
         let input = Cursor::new(INPUT_MIXED_MULTILINE.as_bytes());
         let expected = "multiline";
-        let result = extract_docs(input).unwrap();
+        let result = extract_docs(input, false, false, &no_include).unwrap();
         assert_eq!(result, &[expected]);
     }
 
@@ -189,7 +892,7 @@ mod tests {
     #[test]
     fn extract_docs_nested_level_1() {
         let input = Cursor::new(INPUT_MULTILINE_NESTED_1.as_bytes());
-        let result = extract_docs(input).unwrap();
+        let result = extract_docs(input, false, false, &no_include).unwrap();
         assert_eq!(result, EXPECTED_MULTILINE_NESTED_1);
     }
 
@@ -214,7 +917,326 @@ mod tests {
     #[test]
     fn extract_docs_nested_level_2() {
         let input = Cursor::new(INPUT_MULTILINE_NESTED_2.as_bytes());
-        let result = extract_docs(input).unwrap();
+        let result = extract_docs(input, false, false, &no_include).unwrap();
         assert_eq!(result, EXPECTED_MULTILINE_NESTED_2);
     }
+
+    const INPUT_OUTER_DOCS: &str = "\
+                                    /// first line\n\
+                                    ///\n\
+                                    //// regular comment\n\
+                                    /// second line\n\
+                                    pub use std::any::Any;\n\
+                                    /// docs of another item\n\
+                                    fn main() {}";
+
+    #[test]
+    fn extract_docs_outer_docs() {
+        let reader = Cursor::new(INPUT_OUTER_DOCS.as_bytes());
+        let result = extract_docs(reader, true, false, &no_include).unwrap();
+        assert_eq!(result, &["first line", "", "second line"]);
+    }
+
+    #[test]
+    fn extract_docs_ignore_outer_docs_by_default() {
+        let reader = Cursor::new(INPUT_OUTER_DOCS.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn extract_docs_inner_and_outer_docs() {
+        let input = "//! inner\n//!\n/// outer\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, true, false, &no_include).unwrap();
+        assert_eq!(result, &["inner", "", "outer"]);
+    }
+
+    #[test]
+    fn extract_docs_without_space_after_mark() {
+        let input = "//!no space\n//!\n//! with space\n//!  indented\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["no space", "", "with space", " indented"]);
+    }
+
+    #[test]
+    fn extract_docs_multibyte_after_mark() {
+        let input = "//!\u{1F980} crab\n//! \u{e9}t\u{e9}\n//!\u{e9}\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["\u{1F980} crab", "\u{e9}t\u{e9}", "\u{e9}"]);
+
+        let input = "/*!\u{1F980} crab\n\u{e9}t\u{e9} */";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["\u{1F980} crab", "\u{e9}t\u{e9}"]);
+    }
+
+    #[test]
+    fn extract_docs_include_str() {
+        let input = "//! # Title\n\
+                     #![doc = include_str!(\"../docs/intro.md\")]\n\
+                     //! after\n\
+                     fn main() {}";
+        let include = |path: &str| {
+            assert_eq!(path, "../docs/intro.md");
+            Ok("intro  \n\n```\nlet x = 1;\n```\n".to_owned())
+        };
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &include).unwrap();
+        assert_eq!(
+            result,
+            &["# Title", "intro", "", "```", "let x = 1;", "```", "after"]
+        );
+    }
+
+    #[test]
+    fn extract_docs_only_include_str() {
+        let input = "#![doc = include_str!(\"README.md\")]\nfn main() {}";
+        let include = |_: &str| Ok("included".to_owned());
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &include).unwrap();
+        assert_eq!(result, &["included"]);
+    }
+
+    #[test]
+    fn extract_docs_include_str_error() {
+        let input = "#![doc = include_str!(\"missing.md\")]";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_include_attributes() {
+        assert_eq!(
+            parse_include_attribute("#![doc = include_str!(\"../README.md\")]"),
+            Some("../README.md".to_owned())
+        );
+        assert_eq!(
+            parse_include_attribute("#![doc=include_str!( r\"docs.md\" )]"),
+            Some("docs.md".to_owned())
+        );
+        assert_eq!(parse_include_attribute("#![doc = \"docs.md\"]"), None);
+        assert_eq!(
+            parse_include_attribute("#![doc = include_str!(PATH)]"),
+            None
+        );
+    }
+
+    #[test]
+    fn extract_docs_start_marker() {
+        let input = "//! ignored\n//! <!-- readme-start -->\n//! first\n//! second\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["first", "second"]);
+    }
+
+    #[test]
+    fn extract_docs_stop_marker() {
+        let input = "//! first\n//! second\n//! <!-- readme-stop -->\n//! ignored\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["first", "second"]);
+    }
+
+    #[test]
+    fn extract_docs_start_and_stop_markers() {
+        let input = "/*!\n\
+                     ignored\n\
+                     <!-- readme-start -->\n\
+                     first\n\
+                     ```html\n\
+                     <!-- readme-stop -->\n\
+                     ```\n\
+                     <!-- readme-stop -->\n\
+                     ignored\n\
+                     <!-- readme-start -->\n\
+                     */";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["first", "```html", "<!-- readme-stop -->", "```"]);
+    }
+
+    #[test]
+    fn extract_docs_indented_doc_comments() {
+        let input = "mod inline {\n\
+                     \t//! Docs of a module declared inline\n\
+                     \t//!\n\
+                     \t//! ```\n\
+                     \t//! if true {\n\
+                     \t//!     let x = 1;\n\
+                     \t//! }\n\
+                     \t//! ```\n\
+                     \n\
+                     \x20   //! indented with spaces\n\
+                     \n\
+                     \tfn f() {}\n\
+                     }";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(
+            result,
+            &[
+                "Docs of a module declared inline",
+                "",
+                "```",
+                "if true {",
+                "    let x = 1;",
+                "}",
+                "```",
+                "",
+                "indented with spaces",
+            ]
+        );
+    }
+
+    const INPUT_INLINE_MODULES: &str = "\
+                                        //! Crate docs\n\
+                                        \n\
+                                        pub mod util {\n\
+                                        \x20   //! Utilities\n\
+                                        \x20   //!\n\
+                                        \x20   //! # Examples\n\
+                                        \x20   //!\n\
+                                        \x20   //! ```\n\
+                                        \x20   //! mod not_a_module {\n\
+                                        \x20   //! ```\n\
+                                        \n\
+                                        \x20   fn f() -> &'static str {\n\
+                                        \x20       \"}\"\n\
+                                        \x20   }\n\
+                                        \n\
+                                        \x20   mod inner {\n\
+                                        \x20       //! Inner docs\n\
+                                        \x20   }\n\
+                                        }\n\
+                                        \n\
+                                        mod undocumented {}\n\
+                                        \n\
+                                        mod other {\n\
+                                        \x20   //! Other docs\n\
+                                        }";
+
+    #[test]
+    fn extract_docs_include_modules() {
+        let reader = Cursor::new(INPUT_INLINE_MODULES.as_bytes());
+        let result = extract_docs(reader, false, true, &no_include).unwrap();
+        assert_eq!(
+            result,
+            &[
+                "Crate docs",
+                "",
+                "# util",
+                "",
+                "Utilities",
+                "",
+                "## Examples",
+                "",
+                "```",
+                "mod not_a_module {",
+                "```",
+                "",
+                "## inner",
+                "",
+                "Inner docs",
+                "",
+                "# other",
+                "",
+                "Other docs",
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_docs_include_modules_without_crate_docs() {
+        let input = "mod util {\n    //! Utilities\n}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, true, &no_include).unwrap();
+        assert_eq!(result, &["# util", "", "Utilities"]);
+    }
+
+    #[test]
+    fn scan_braces_outside_strings_and_comments() {
+        let mut code = CodeScanner::new();
+        assert_eq!(code.scan("fn f() {"), 1);
+        assert_eq!(code.scan("let s = \"{\"; // }"), 0);
+        assert_eq!(code.scan("match c { '{' => {}, '\\'' => {} }"), 0);
+        assert_eq!(code.scan("fn f<'a>(x: &'a str) {"), 1);
+        assert_eq!(code.scan("let s = br#\"{\"#; /* { */"), 0);
+        assert_eq!(code.scan("}"), -1);
+        assert!(code.in_code());
+    }
+
+    #[test]
+    fn scan_multiline_strings_and_comments() {
+        let mut code = CodeScanner::new();
+        assert_eq!(code.scan("let s = \"{"), 0);
+        assert!(!code.in_code());
+        assert_eq!(code.scan("\\\" }\"; {"), 1);
+        assert!(code.in_code());
+        assert_eq!(code.scan("let s = r##\"\"#"), 0);
+        assert!(!code.in_code());
+        assert_eq!(code.scan("}\"##; /* /* */"), 0);
+        assert!(!code.in_code());
+        assert_eq!(code.scan("} */ }"), -1);
+        assert!(code.in_code());
+    }
+
+    // lines looking like doc comments in code, which are never docs of the crate
+    const INPUT_FALSE_DOC_COMMENTS: &[&str] = &[
+        "fn main() {",
+        "    let a = \"//! in a string\";",
+        "    let b = 1; //! after code",
+        "    let c = \"",
+        "//! in a multiline string",
+        "    \";",
+        "    let d = r#\"",
+        "    //! in a raw string, with a \" inside",
+        "    \"#;",
+        "    /*",
+        "    //! in a block comment /* nested */",
+        "    */",
+        "    let e = '\"';",
+        "}",
+    ];
+
+    #[test]
+    fn extract_docs_ignores_false_doc_comments() {
+        let input = INPUT_FALSE_DOC_COMMENTS.join("\n");
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, true, false, &no_include).unwrap();
+        assert!(result.is_empty(), "unexpected docs: {:?}", result);
+    }
+
+    #[test]
+    fn extract_docs_after_false_doc_comments() {
+        let input = format!(
+            "{}\nmod inline {{\n    //! real docs\n}}",
+            INPUT_FALSE_DOC_COMMENTS.join("\n")
+        );
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["real docs"]);
+    }
+
+    #[test]
+    fn extract_docs_include_modules_ignores_false_doc_comments() {
+        let input = format!(
+            "mod inline {{\n{}\n}}\nmod other {{\n    //! real docs\n}}",
+            INPUT_FALSE_DOC_COMMENTS.join("\n")
+        );
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, true, &no_include).unwrap();
+        assert_eq!(result, &["# other", "", "real docs"]);
+    }
+
+    #[test]
+    fn extract_docs_slash_after_mark() {
+        let input = "//!/ starts with a slash\n//!// two slashes\nfn main() {}";
+        let reader = Cursor::new(input.as_bytes());
+        let result = extract_docs(reader, false, false, &no_include).unwrap();
+        assert_eq!(result, &["/ starts with a slash", "// two slashes"]);
+    }
 }
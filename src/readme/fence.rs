@@ -0,0 +1,116 @@
+//! Track fenced code blocks in markdown
+//!
+//! Used by the processing of the docs to find the code blocks to transform, and by the
+//! transformations applied after it, so they leave code blocks untouched.
+
+use regex::{Captures, Regex};
+
+lazy_static! {
+    // Is this the start of a code block? The info string has the language and rustdoc attributes
+    static ref RE_CODE_BLOCK: Regex = Regex::new(r"^(?P<indent> {0,3})(?P<delimiter>`{3,}|~{3,})(?P<info>.*)$").unwrap();
+}
+
+/// Keeps track of whether the lines being read are inside a fenced code block
+pub struct FenceTracker {
+    delimiter: Option<String>,
+}
+
+impl FenceTracker {
+    pub fn new() -> Self {
+        FenceTracker { delimiter: None }
+    }
+
+    /// Read the next line and tell if it is part of a code block, including the fences
+    pub fn is_code(&mut self, line: &str) -> bool {
+        match self.delimiter.take() {
+            Some(delimiter) => {
+                if !is_code_block_end(line, &delimiter) {
+                    self.delimiter = Some(delimiter);
+                }
+                true
+            }
+            None => {
+                self.delimiter = code_block_start(line).map(|cap| cap["delimiter"].to_owned());
+                self.delimiter.is_some()
+            }
+        }
+    }
+
+    /// Get the delimiter of the code block still open after the lines read, if any
+    pub fn open_delimiter(&self) -> Option<&str> {
+        self.delimiter.as_deref()
+    }
+}
+
+/// Match the start of a fenced code block
+///
+/// Like in CommonMark, the fence is at least 3 backticks or tildes, indented by up to 3 spaces,
+/// and the info string of a backtick fence cannot contain backticks, otherwise it is inline code.
+pub fn code_block_start(line: &str) -> Option<Captures<'_>> {
+    RE_CODE_BLOCK
+        .captures(line)
+        .filter(|cap| !cap["delimiter"].starts_with('`') || !cap["info"].contains('`'))
+}
+
+/// Does the line close the code block opened with `delimiter`?
+///
+/// Like in CommonMark, the closing fence uses the same character as the opening one, at least as
+/// many times, is indented by up to 3 spaces and is followed only by whitespace.
+pub fn is_code_block_end(line: &str, delimiter: &str) -> bool {
+    let fence = line.trim_start_matches(' ');
+    if line.len() - fence.len() > 3 {
+        return false;
+    }
+
+    let fence_char = delimiter.chars().next().unwrap_or('`');
+    let rest = fence.trim_start_matches(fence_char);
+    fence.len() - rest.len() >= delimiter.len() && rest.trim().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FenceTracker;
+
+    #[test]
+    fn track_code_blocks() {
+        let lines = &[
+            "text",
+            "```rust",
+            "code",
+            "~~~",
+            "```",
+            "text",
+            "````",
+            "```",
+            "````",
+            "```inline``` text",
+        ];
+        let expected = &[
+            false, true, true, true, true, false, true, true, true, false,
+        ];
+
+        let mut tracker = FenceTracker::new();
+        let result: Vec<bool> = lines.iter().map(|l| tracker.is_code(l)).collect();
+        assert_eq!(result, expected);
+        assert_eq!(None, tracker.open_delimiter());
+    }
+
+    #[test]
+    fn ignore_fences_indented_by_four_spaces() {
+        let lines = &["   ```", "code", "    ```", "```", "    ```", "text"];
+        let expected = &[true, true, true, true, false, false];
+
+        let mut tracker = FenceTracker::new();
+        let result: Vec<bool> = lines.iter().map(|l| tracker.is_code(l)).collect();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn track_unclosed_code_block() {
+        let mut tracker = FenceTracker::new();
+        for line in &["text", "~~~~toml", "[package]", "~~~"] {
+            tracker.is_code(line);
+        }
+        assert_eq!(Some("~~~~"), tracker.open_delimiter());
+    }
+}
@@ -0,0 +1,372 @@
+//! Find the markdown headings and generate their anchors and the table of contents
+//!
+//! The anchors are the slugs GitHub generates for headings, so links to them work both with the
+//! anchors added by cargo-readme and with the ones added by GitHub.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use super::fence::FenceTracker;
+
+lazy_static! {
+    // ATX heading, like "## Heading" or "## Heading ##"
+    static ref RE_HEADING: Regex = Regex::new(r"^ {0,3}(?P<level>#{1,6})(?:[ \t]+(?P<text>.*?))?(?:[ \t]+#+)?[ \t]*$").unwrap();
+    // Inline link, like "[text](target)", of which only the text is part of the slug
+    static ref RE_LINK: Regex = Regex::new(r"\[(?P<text>[^\[\]]*)\]\([^()]*\)").unwrap();
+}
+
+/// Get the level and the text of a heading line
+pub fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    RE_HEADING.captures(line).map(|cap| {
+        let level = cap.name("level").unwrap().as_str().len();
+        let text = cap.name("text").map_or("", |text| text.as_str());
+        (level, text)
+    })
+}
+
+/// Generate the slug of a heading like GitHub does
+///
+/// The text is lowercased, punctuation is removed and spaces are replaced by "-". Links are
+/// replaced by their text.
+pub fn slug(text: &str) -> String {
+    RE_LINK
+        .replace_all(text.trim(), "$text")
+        .to_lowercase()
+        .chars()
+        .filter(|&c| c.is_alphanumeric() || c == '_' || c == '-' || c == ' ')
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+/// Generate unique slugs for the headings of a document
+///
+/// Like GitHub, the repeated slugs get a "-1", "-2"... suffix.
+pub struct Slugger {
+    occurrences: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn new() -> Self {
+        Slugger {
+            occurrences: HashMap::new(),
+        }
+    }
+
+    /// Get the slug of the next heading
+    pub fn slug(&mut self, text: &str) -> String {
+        let base = slug(text);
+        let mut result = base.clone();
+
+        if let Some(&count) = self.occurrences.get(&base) {
+            let mut count = count;
+            loop {
+                count += 1;
+                result = format!("{}-{}", base, count);
+                if !self.occurrences.contains_key(&result) {
+                    break;
+                }
+            }
+            self.occurrences.insert(base, count);
+        }
+
+        self.occurrences.insert(result.clone(), 0);
+        result
+    }
+}
+
+/// Add an html anchor before each heading outside of code blocks
+///
+/// The anchor is on its own line, like `<a name="slug"></a>`, so the heading is left as is.
+pub fn add_anchors(lines: Vec<String>) -> Vec<String> {
+    let mut fence = FenceTracker::new();
+    let mut slugger = Slugger::new();
+    let mut result = Vec::with_capacity(lines.len());
+
+    for line in lines {
+        if !fence.is_code(&line) {
+            if let Some((_, text)) = parse_heading(&line) {
+                result.push(format!("<a name=\"{}\"></a>", slugger.slug(text)));
+            }
+        }
+        result.push(line);
+    }
+
+    result
+}
+
+/// Remove the sections whose heading text is one of `names`, compared case-insensitively
+///
+/// A section goes from its heading up to the next heading of the same or a higher level, so its
+/// subsections are removed too. Headings inside code blocks are ignored.
+pub fn exclude_sections(lines: Vec<String>, names: &[String]) -> Vec<String> {
+    let names: Vec<String> = names
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let mut fence = FenceTracker::new();
+    // level of the heading of the section being removed
+    let mut excluded: Option<usize> = None;
+    let mut result = Vec::with_capacity(lines.len());
+
+    for line in lines {
+        if !fence.is_code(&line) {
+            if let Some((level, text)) = parse_heading(&line) {
+                if excluded.is_none_or(|excluded| level <= excluded) {
+                    excluded = if names.contains(&text.trim().to_lowercase()) {
+                        Some(level)
+                    } else {
+                        None
+                    };
+                }
+            }
+        }
+
+        if excluded.is_none() {
+            result.push(line);
+        }
+    }
+
+    result
+}
+
+/// Check if the readme has a "License" or "Licensing" section, at any level
+///
+/// The heading text is compared case-insensitively. Headings inside code blocks are ignored.
+pub fn has_license_section(readme: &str) -> bool {
+    let mut fence = FenceTracker::new();
+
+    readme.lines().any(|line| {
+        if fence.is_code(line) {
+            return false;
+        }
+
+        match parse_heading(line) {
+            Some((_, text)) => {
+                let text = text.trim().to_lowercase();
+                text == "license" || text == "licensing"
+            }
+            None => false,
+        }
+    })
+}
+
+/// Render the headings of the readme as a nested list of links to their anchors
+///
+/// The nesting follows the heading levels, the headings at the highest level found are at the top
+/// of the list. Headings inside code blocks are ignored.
+pub fn table_of_contents(readme: &str) -> String {
+    let mut fence = FenceTracker::new();
+    let mut slugger = Slugger::new();
+    // levels of the headings containing the current one
+    let mut parents: Vec<usize> = Vec::new();
+    let mut result = Vec::new();
+
+    for line in readme.lines() {
+        if fence.is_code(line) {
+            continue;
+        }
+
+        let (level, text) = match parse_heading(line) {
+            Some(heading) => heading,
+            None => continue,
+        };
+
+        while parents.last().is_some_and(|&parent| parent >= level) {
+            parents.pop();
+        }
+
+        result.push(format!(
+            "{}- [{}](#{})",
+            "  ".repeat(parents.len()),
+            RE_LINK.replace_all(text, "$text"),
+            slugger.slug(text)
+        ));
+
+        parents.push(level);
+    }
+
+    result.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_anchors, exclude_sections, has_license_section, parse_heading, slug, table_of_contents,
+        Slugger,
+    };
+
+    #[test]
+    fn parse_headings() {
+        assert_eq!(parse_heading("# Title"), Some((1, "Title")));
+        assert_eq!(parse_heading("### Title ###"), Some((3, "Title")));
+        assert_eq!(parse_heading("  ## Title"), Some((2, "Title")));
+        assert_eq!(parse_heading("##"), Some((2, "")));
+        assert_eq!(parse_heading("#Title"), None);
+        assert_eq!(parse_heading("####### Title"), None);
+        assert_eq!(parse_heading("    # Title"), None);
+        assert_eq!(parse_heading("Title"), None);
+    }
+
+    #[test]
+    fn slug_with_punctuation() {
+        assert_eq!(slug("Hello, World!"), "hello-world");
+        assert_eq!(slug("What's `new` in 2.0?"), "whats-new-in-20");
+        assert_eq!(
+            slug("snake_case and kebab-case"),
+            "snake_case-and-kebab-case"
+        );
+        assert_eq!(slug("See [the docs](https://docs.rs)"), "see-the-docs");
+        assert_eq!(slug("Ünïcödé"), "ünïcödé");
+    }
+
+    #[test]
+    fn slug_duplicate_headings() {
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.slug("Usage"), "usage");
+        assert_eq!(slugger.slug("Usage"), "usage-1");
+        assert_eq!(slugger.slug("Usage-1"), "usage-1-1");
+        assert_eq!(slugger.slug("Usage"), "usage-2");
+    }
+
+    #[test]
+    fn add_anchors_outside_code_blocks() {
+        let lines: Vec<String> = vec![
+            "# Usage",
+            "",
+            "```sh",
+            "# not a heading",
+            "```",
+            "",
+            "## Usage!",
+        ]
+        .into_iter()
+        .map(|l| l.to_owned())
+        .collect();
+
+        let expected = &[
+            "<a name=\"usage\"></a>",
+            "# Usage",
+            "",
+            "```sh",
+            "# not a heading",
+            "```",
+            "",
+            "<a name=\"usage-1\"></a>",
+            "## Usage!",
+        ];
+
+        assert_eq!(add_anchors(lines), expected);
+    }
+
+    fn exclude(lines: &[&str], names: &[&str]) -> Vec<String> {
+        exclude_sections(
+            lines.iter().map(|line| line.to_string()).collect(),
+            &names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn exclude_middle_section() {
+        let lines = &[
+            "# Usage",
+            "",
+            "Use it",
+            "",
+            "# Internal",
+            "",
+            "Not for the readme",
+            "",
+            "## Details",
+            "",
+            "```sh",
+            "# not a heading",
+            "```",
+            "",
+            "# Examples",
+            "",
+            "An example",
+        ];
+        assert_eq!(
+            exclude(lines, &["internal"]),
+            &["# Usage", "", "Use it", "", "# Examples", "", "An example"]
+        );
+    }
+
+    #[test]
+    fn exclude_several_sections() {
+        let lines = &[
+            "## First",
+            "",
+            "### Internal",
+            "",
+            "hidden",
+            "",
+            "### Kept",
+            "",
+            "## Private notes",
+            "",
+            "hidden",
+        ];
+        assert_eq!(
+            exclude(lines, &["INTERNAL", " private notes "]),
+            &["## First", "", "### Kept", ""]
+        );
+    }
+
+    #[test]
+    fn license_section_at_any_level() {
+        assert!(has_license_section("text\n\n# License\n\nMIT"));
+        assert!(has_license_section("text\n\n### licensing ###\n\nMIT"));
+        assert!(has_license_section("## LICENSE"));
+    }
+
+    #[test]
+    fn license_section_not_found() {
+        assert!(!has_license_section("text\n\n## Usage\n\nLicense: MIT"));
+        assert!(!has_license_section("## License notes"));
+        assert!(!has_license_section("```sh\n# License\n```"));
+    }
+
+    #[test]
+    fn table_of_contents_nested_headings() {
+        let readme = "## Usage\n\
+                      \n\
+                      ### Install\n\
+                      \n\
+                      ```sh\n\
+                      # not a heading\n\
+                      ```\n\
+                      \n\
+                      #### From [crates.io](https://crates.io)\n\
+                      \n\
+                      ### Run\n\
+                      \n\
+                      ## Usage";
+
+        let expected = "- [Usage](#usage)\n\
+                        \x20 - [Install](#install)\n\
+                        \x20   - [From crates.io](#from-cratesio)\n\
+                        \x20 - [Run](#run)\n\
+                        - [Usage](#usage-1)";
+
+        assert_eq!(table_of_contents(readme), expected);
+    }
+
+    #[test]
+    fn table_of_contents_skipped_levels() {
+        let readme = "### Deep\n\n# Top\n\n### Nested";
+        assert_eq!(
+            table_of_contents(readme),
+            "- [Deep](#deep)\n- [Top](#top)\n  - [Nested](#nested)"
+        );
+    }
+
+    #[test]
+    fn table_of_contents_without_headings() {
+        assert_eq!(table_of_contents("text\n\n```\n# code\n```"), "");
+    }
+}
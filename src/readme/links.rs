@@ -0,0 +1,580 @@
+//! Rewrite links that only make sense in rustdoc
+//!
+//! Intra-doc links, like "[`Item`]" or "[text](crate::module::Item)", are resolved by rustdoc but
+//! are broken in a README, so they are rewritten to point to the documentation on docs.rs.
+//!
+//! Relative links to files of the repository, like "[the guide](docs/guide.md)", work on the
+//! repository page but not on crates.io, so they can be rewritten into absolute urls.
+//!
+//! Reference links, like "[text][id]", need a "[id]: target" definition, which can be lost when
+//! parts of the docs are left out, so the references without definition are reported.
+
+use regex::{Captures, Regex};
+
+use super::fence::FenceTracker;
+
+lazy_static! {
+    // Link with explicit target, like "[text](target)"
+    static ref RE_LINK: Regex = Regex::new(r"\[(?P<text>[^\[\]]+)\]\((?P<target>[^()\s]*(?:\(\))?)\)").unwrap();
+    // Shorthand link, like "[Item]" or "[`Item`]"
+    static ref RE_SHORTHAND_LINK: Regex = Regex::new(r"\[(?P<text>`?(?P<target>[\w@:!()]+?)`?)\]").unwrap();
+    // Rust path with optional disambiguator, like "struct@crate::module::Item" or "macro!"
+    static ref RE_RUST_PATH: Regex = Regex::new(r"^(?:(?P<kind>[a-z]+)@)?(?P<path>(?:\w+::)*\w+)(?P<suffix>!|\(\))?$").unwrap();
+    // Reference link definition, like "[Item]: https://..."
+    static ref RE_DEFINITION: Regex = Regex::new(r"^ {0,3}\[(?P<id>[^\]]+)\]:").unwrap();
+    // Reference link definition with its target, like "[guide]: docs/guide.md"
+    static ref RE_DEFINITION_TARGET: Regex = Regex::new(r"^(?P<definition> {0,3}\[[^\]]+\]:[ \t]*)(?P<target>\S+)").unwrap();
+    // Full or collapsed reference link, like "[text][id]" or "[id][]", whose text can hold an
+    // image, like "[![badge](image)][id]"
+    static ref RE_REFERENCE: Regex = Regex::new(r"\[(?P<text>(?:[^\[\]]|\[[^\[\]]*\])*)\]\[(?P<id>[^\[\]]*)\]").unwrap();
+    // Shortcut reference link, like "[id]", or any text in brackets
+    static ref RE_SHORTCUT: Regex = Regex::new(r"\[(?P<id>[^\[\]]+)\]").unwrap();
+    // Url with a scheme, like "https://..." or "mailto:..."
+    static ref RE_SCHEME: Regex = Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*:").unwrap();
+}
+
+/// Rewrite intra-doc links into links to the crate documentation on docs.rs
+///
+/// When the kind of the item is given by a disambiguator, like "struct@Item", "macro!" or
+/// "func()", links point to the page of the item, like ".../my_crate/module/struct.Item.html".
+/// Otherwise the kind is not known, so they point to a search for the item path.
+/// Shorthand links are only rewritten if they are in backticks or look like a path or a type
+/// name (so "[x]" is left alone), and if there is no reference definition with that name.
+/// Reference definitions whose target is an item path, like "[Item]: crate::module::Item", are
+/// rewritten too. Links inside code are not touched.
+pub fn expand_intra_links(lines: Vec<String>, crate_name: &str) -> Vec<String> {
+    let definitions: Vec<String> = lines
+        .iter()
+        .filter_map(|line| RE_DEFINITION.captures(line))
+        .map(|cap| cap["id"].to_lowercase())
+        .collect();
+
+    let mut fence = FenceTracker::new();
+
+    lines
+        .into_iter()
+        .map(|line| {
+            if fence.is_code(&line) {
+                return line;
+            }
+
+            if let Some(cap) = RE_DEFINITION_TARGET.captures(&line) {
+                if !is_item_path(&cap["target"]) {
+                    return line;
+                }
+                return match docs_url(crate_name, &cap["target"]) {
+                    Some(url) => format!(
+                        "{}{}{}",
+                        &cap["definition"],
+                        url,
+                        &line[cap.get(0).unwrap().end()..]
+                    ),
+                    None => line,
+                };
+            }
+
+            let line = replace_outside_code_spans(&RE_LINK, &line, |cap, _, _| {
+                let target = &cap["target"];
+                if !is_item_path(target) {
+                    return None;
+                }
+                docs_url(crate_name, target).map(|url| format!("[{}]({})", &cap["text"], url))
+            });
+
+            replace_outside_code_spans(&RE_SHORTHAND_LINK, &line, |cap, previous, next| {
+                // "[text](target)", "[text][id]" and "[id]: target" are not shorthand links
+                if previous == Some(']')
+                    || next == Some('(')
+                    || next == Some('[')
+                    || next == Some(':')
+                {
+                    return None;
+                }
+
+                let text = &cap["text"];
+                let target = &cap["target"];
+
+                if !is_intra_doc_shortcut(text) {
+                    return None;
+                }
+
+                if definitions.contains(&text.to_lowercase()) {
+                    return None;
+                }
+
+                docs_url(crate_name, target).map(|url| format!("[{}]({})", text, url))
+            })
+        })
+        .collect()
+}
+
+/// Rewrite the links to files of the repository into absolute urls, using its `repository` url
+///
+/// Links are relative to the root of the repository, like "docs/guide.md", which becomes
+/// "<repository>/blob/HEAD/docs/guide.md". Images use "raw" instead of "blob", so they are still
+/// displayed. Anchors, absolute paths, urls and intra-doc links are left alone, and so are links
+/// inside code.
+pub fn rewrite_relative_links(lines: Vec<String>, repository: &str) -> Vec<String> {
+    let repository = repository.trim_end_matches('/');
+    let repository = repository.strip_suffix(".git").unwrap_or(repository);
+
+    let mut fence = FenceTracker::new();
+
+    lines
+        .into_iter()
+        .map(|line| {
+            if fence.is_code(&line) {
+                return line;
+            }
+
+            if let Some(cap) = RE_DEFINITION_TARGET.captures(&line) {
+                if is_relative(&cap["target"]) {
+                    let url = repository_url(repository, "blob", &cap["target"]);
+                    return format!(
+                        "{}{}{}",
+                        &cap["definition"],
+                        url,
+                        &line[cap.get(0).unwrap().end()..]
+                    );
+                }
+                return line;
+            }
+
+            replace_outside_code_spans(&RE_LINK, &line, |cap, previous, _| {
+                let target = &cap["target"];
+                if !is_relative(target) {
+                    return None;
+                }
+                let kind = if previous == Some('!') { "raw" } else { "blob" };
+                Some(format!(
+                    "[{}]({})",
+                    &cap["text"],
+                    repository_url(repository, kind, target)
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Get the ids of the reference link definitions, like "[id]: target", normalized like in markdown
+pub fn reference_definitions(lines: &[String]) -> Vec<String> {
+    let mut fence = FenceTracker::new();
+
+    lines
+        .iter()
+        .filter(|line| !fence.is_code(line))
+        .filter_map(|line| RE_DEFINITION.captures(line))
+        .map(|cap| normalize_label(&cap["id"]))
+        .collect()
+}
+
+/// Get the ids of the reference links, like "[text][id]" or "[id][]", which have no "[id]: target"
+/// definition
+///
+/// Ids are compared like in markdown, ignoring case and extra whitespace, and are returned once,
+/// in order of appearance. Shortcut references, like "[id]", are only checked if their definition
+/// was lost, that is if it is in `docs_definitions`, the definitions of the docs before they were
+/// transformed, since text in brackets, like "[1]", is valid markdown. References inside code are
+/// ignored.
+pub fn dangling_references(lines: &[String], docs_definitions: &[String]) -> Vec<String> {
+    let mut fence = FenceTracker::new();
+    let mut definitions = Vec::new();
+    let mut references: Vec<String> = Vec::new();
+
+    for line in lines {
+        if fence.is_code(line) {
+            continue;
+        }
+
+        if let Some(cap) = RE_DEFINITION.captures(line) {
+            definitions.push(normalize_label(&cap["id"]));
+            continue;
+        }
+
+        let full = RE_REFERENCE.captures_iter(line).map(|cap| {
+            let id = if cap["id"].trim().is_empty() {
+                cap.name("text").unwrap()
+            } else {
+                cap.name("id").unwrap()
+            };
+            (cap.get(0).unwrap().start(), id.as_str())
+        });
+        let shortcuts = RE_SHORTCUT
+            .captures_iter(line)
+            .filter(|cap| {
+                is_shortcut_reference(line, cap)
+                    && docs_definitions.contains(&normalize_label(&cap["id"]))
+            })
+            .map(|cap| {
+                (
+                    cap.get(0).unwrap().start(),
+                    cap.name("id").unwrap().as_str(),
+                )
+            });
+
+        let mut found: Vec<(usize, &str)> = full.chain(shortcuts).collect();
+        found.sort_by_key(|&(start, _)| start);
+
+        for (start, id) in found {
+            // an odd number of backticks before the match means it is inside inline code
+            if line[..start].matches('`').count() % 2 == 1 {
+                continue;
+            }
+
+            if !references
+                .iter()
+                .any(|r| normalize_label(r) == normalize_label(id))
+            {
+                references.push(id.to_owned());
+            }
+        }
+    }
+
+    references
+        .into_iter()
+        .filter(|id| !definitions.contains(&normalize_label(id)))
+        .collect()
+}
+
+/// Is this text in brackets a shortcut reference link, not part of another kind of link?
+fn is_shortcut_reference(line: &str, cap: &Captures) -> bool {
+    let m = cap.get(0).unwrap();
+    let previous = line[..m.start()].chars().next_back();
+    let next = line[m.end()..].chars().next();
+
+    // "[text](target)", "[text][id]" and "[id]: target" are handled elsewhere
+    previous != Some(']') && next != Some('(') && next != Some('[') && next != Some(':')
+}
+
+/// Is the text of a shorthand link an intra-doc link, in backticks or looking like a path or a
+/// type name, like "[`func`]", "[module::Item]" or "[Item]", rather than a word in brackets?
+fn is_intra_doc_shortcut(text: &str) -> bool {
+    let backticks = text.starts_with('`') && text.ends_with('`') && text.len() > 1;
+    let target = text.trim_matches('`');
+    let type_like = target.chars().next().is_some_and(char::is_uppercase);
+
+    RE_RUST_PATH.is_match(target) && (backticks || type_like || target.contains("::"))
+}
+
+/// Normalize a reference label, so "[Some  Id]" matches "[some id]"
+fn normalize_label(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Is the link target a path relative to the repository, not an anchor, url or intra-doc link
+///
+/// Targets which parse as a Rust path, like "Vec", "f()" or "crate::Item", are intra-doc links,
+/// so a file without extension at the root of the repository, like "LICENSE", is not rewritten.
+fn is_relative(target: &str) -> bool {
+    !target.is_empty()
+        && !target.starts_with('#')
+        && !target.starts_with('/')
+        && !RE_RUST_PATH.is_match(target)
+        && !RE_SCHEME.is_match(target)
+}
+
+/// Build the url of a file of the repository, `kind` being "blob" or "raw"
+fn repository_url(repository: &str, kind: &str, path: &str) -> String {
+    let path = path.trim_start_matches("./");
+    format!("{}/{}/HEAD/{}", repository, kind, path)
+}
+
+/// Replace the matches of `re` that are not inside inline code
+///
+/// `replace` receives the match and the characters around it, and returns `None` to keep the
+/// match unchanged.
+fn replace_outside_code_spans<F>(re: &Regex, line: &str, mut replace: F) -> String
+where
+    F: FnMut(&Captures, Option<char>, Option<char>) -> Option<String>,
+{
+    let mut result = String::with_capacity(line.len());
+    let mut last = 0;
+
+    for cap in re.captures_iter(line) {
+        let m = cap.get(0).unwrap();
+
+        // an odd number of backticks before the match means it is inside inline code
+        if line[..m.start()].matches('`').count() % 2 == 1 {
+            continue;
+        }
+
+        let previous = line[..m.start()].chars().next_back();
+        let next = line[m.end()..].chars().next();
+
+        if let Some(replacement) = replace(&cap, previous, next) {
+            result.push_str(&line[last..m.start()]);
+            result.push_str(&replacement);
+            last = m.end();
+        }
+    }
+
+    result.push_str(&line[last..]);
+    result
+}
+
+/// Is the link target surely an item path, with a module path or a disambiguator, rather than a
+/// word in brackets or a file?
+fn is_item_path(target: &str) -> bool {
+    RE_RUST_PATH.captures(target).is_some_and(|cap| {
+        cap["path"].contains("::") || cap.name("kind").is_some() || cap.name("suffix").is_some()
+    })
+}
+
+/// Get the prefix of the page of an item of this kind, like "struct" in "struct.Item.html"
+///
+/// `None` is returned for modules, whose page is "module/index.html", and the kinds without a
+/// page of their own, like "method" or "field".
+fn page_prefix(kind: &str) -> Option<&'static str> {
+    match kind {
+        "struct" => Some("struct"),
+        "enum" => Some("enum"),
+        "trait" => Some("trait"),
+        "union" => Some("union"),
+        "type" => Some("type"),
+        "const" | "constant" => Some("constant"),
+        "static" => Some("static"),
+        "fn" | "function" => Some("fn"),
+        "macro" => Some("macro"),
+        "derive" => Some("derive"),
+        "attr" => Some("attr"),
+        _ => None,
+    }
+}
+
+/// Build the url of the item of an intra-doc link target on docs.rs
+///
+/// The url is the page of the item if its kind is known from the disambiguator, or else a search
+/// for its path, without `crate::`.
+fn docs_url(crate_name: &str, target: &str) -> Option<String> {
+    let cap = RE_RUST_PATH.captures(target)?;
+    let path = &cap["path"];
+    let path = path.strip_prefix("crate::").unwrap_or(path);
+
+    let kind = match (cap.name("kind"), cap.name("suffix")) {
+        (Some(kind), _) => kind.as_str(),
+        (None, Some(suffix)) if suffix.as_str() == "!" => "macro",
+        (None, Some(_)) => "fn",
+        (None, None) => "",
+    };
+
+    let root = format!(
+        "https://docs.rs/{}/latest/{}",
+        crate_name,
+        crate_name.replace("-", "_")
+    );
+    let (module, name) = match path.rfind("::") {
+        Some(i) => (format!("/{}", path[..i].replace("::", "/")), &path[i + 2..]),
+        None => (String::new(), path),
+    };
+
+    let url = match kind {
+        "mod" | "module" => format!("{}/{}/index.html", root, path.replace("::", "/")),
+        _ => match page_prefix(kind) {
+            Some(prefix) => format!("{}{}/{}.{}.html", root, module, prefix, name),
+            None => format!("{}/?search={}", root, path),
+        },
+    };
+
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        dangling_references, expand_intra_links, reference_definitions, rewrite_relative_links,
+    };
+
+    fn expand(lines: &[&str]) -> Vec<String> {
+        let lines = lines.iter().map(|l| l.to_string()).collect();
+        expand_intra_links(lines, "my-crate")
+    }
+
+    #[test]
+    fn expand_link_with_path() {
+        let result = expand(&["See [the module](crate::module::Item)."]);
+        assert_eq!(
+            result,
+            &["See [the module](https://docs.rs/my-crate/latest/my_crate/?search=module::Item)."]
+        );
+    }
+
+    #[test]
+    fn expand_shorthand_links() {
+        let result = expand(&["Use [`Item`], [Other] or [`func()`]."]);
+        assert_eq!(
+            result,
+            &[
+                "Use [`Item`](https://docs.rs/my-crate/latest/my_crate/?search=Item), \
+               [Other](https://docs.rs/my-crate/latest/my_crate/?search=Other) or \
+               [`func()`](https://docs.rs/my-crate/latest/my_crate/fn.func.html)."
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_links_to_item_pages() {
+        let result = expand(&[
+            "[`Item`](struct@crate::module::Item), [log!](crate::log!), [run](crate::run()),",
+            "[the module](mod@crate::module) and [Shape](enum@Shape)",
+        ]);
+        assert_eq!(
+            result,
+            &[
+                "[`Item`](https://docs.rs/my-crate/latest/my_crate/module/struct.Item.html), \
+                 [log!](https://docs.rs/my-crate/latest/my_crate/macro.log.html), \
+                 [run](https://docs.rs/my-crate/latest/my_crate/fn.run.html),",
+                "[the module](https://docs.rs/my-crate/latest/my_crate/module/index.html) and \
+                 [Shape](https://docs.rs/my-crate/latest/my_crate/enum.Shape.html)",
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_reference_definitions() {
+        let result = expand(&[
+            "See [Item] and [the trait][Read].",
+            "",
+            "[Item]: crate::module::Item",
+            "[Read]: trait@crate::io::Read",
+            "[guide]: docs/guide.md",
+        ]);
+        assert_eq!(
+            result,
+            &[
+                "See [Item] and [the trait][Read].",
+                "",
+                "[Item]: https://docs.rs/my-crate/latest/my_crate/?search=module::Item",
+                "[Read]: https://docs.rs/my-crate/latest/my_crate/io/trait.Read.html",
+                "[guide]: docs/guide.md",
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_regular_links() {
+        let input = &[
+            "[docs](https://docs.rs) and [guide](docs/guide.md)",
+            "- [x] done",
+            "[Reference][ref] and [text][Item]",
+            "",
+            "[Item]: https://example.com",
+            "[Item] is defined above",
+            "`[Item]` is code",
+        ];
+        assert_eq!(expand(input), input);
+    }
+
+    #[test]
+    fn keep_links_in_code_blocks() {
+        let input = &["```rust", "let a = [Item];", "```"];
+        assert_eq!(expand(input), input);
+    }
+
+    fn rewrite(lines: &[&str]) -> Vec<String> {
+        let lines = lines.iter().map(|l| l.to_string()).collect();
+        rewrite_relative_links(lines, "https://github.com/me/my-crate.git")
+    }
+
+    #[test]
+    fn rewrite_relative_link() {
+        let result = rewrite(&[
+            "See [the guide](docs/guide.md#setup) and ![logo](./assets/logo.png).",
+            "[changelog]: CHANGELOG.md",
+        ]);
+        assert_eq!(
+            result,
+            &[
+                "See [the guide](https://github.com/me/my-crate/blob/HEAD/docs/guide.md#setup) \
+                 and ![logo](https://github.com/me/my-crate/raw/HEAD/assets/logo.png).",
+                "[changelog]: https://github.com/me/my-crate/blob/HEAD/CHANGELOG.md",
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_anchors_and_absolute_links() {
+        let input = &[
+            "See [the section](#setup) and [docs](https://docs.rs/my-crate).",
+            "[Item](crate::Item), [root](/index.html) and [mail](mailto:me@example.com)",
+            "[Vec](Vec), [f](f()) and [`T`](T)",
+            "[docs]: https://docs.rs",
+            "`[code](docs/guide.md)`",
+            "```markdown",
+            "[guide](docs/guide.md)",
+            "```",
+        ];
+        assert_eq!(rewrite(input), input);
+    }
+
+    fn dangling(lines: &[&str]) -> Vec<String> {
+        dangling_from(lines, lines)
+    }
+
+    /// Dangling references of `lines`, which were transformed from `docs`
+    fn dangling_from(docs: &[&str], lines: &[&str]) -> Vec<String> {
+        let docs: Vec<String> = docs.iter().map(|l| l.to_string()).collect();
+        let lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        dangling_references(&lines, &reference_definitions(&docs))
+    }
+
+    #[test]
+    fn reference_with_definition() {
+        let input = &[
+            "See [the guide][guide], [Docs][] and [![badge](badge.svg)][CI Status].",
+            "",
+            "[guide]: docs/guide.md",
+            "[docs]: https://docs.rs",
+            "[ci  status]: https://ci.example.com",
+        ];
+        assert!(dangling(input).is_empty());
+    }
+
+    #[test]
+    fn dangling_reference() {
+        let input = &[
+            "See [the guide][guide], [Docs][] and [the guide][Guide] again.",
+            "",
+            "[other]: https://example.com",
+        ];
+        assert_eq!(dangling(input), &["guide", "Docs"]);
+    }
+
+    #[test]
+    fn dangling_shortcut_reference() {
+        let docs = &[
+            "See [the guide] and [foo].",
+            "",
+            "## Links",
+            "",
+            "[The  Guide]: docs/guide.md",
+            "[foo]: https://example.com",
+        ];
+        let lines = &[
+            "See [the guide] and [foo].",
+            "",
+            "[The  Guide]: docs/guide.md",
+        ];
+        assert_eq!(dangling_from(docs, lines), &["foo"]);
+    }
+
+    #[test]
+    fn ignore_text_in_brackets() {
+        let input = &["See [1] and [deprecated], or [x] and [Item]."];
+        assert!(dangling(input).is_empty());
+    }
+
+    #[test]
+    fn ignore_references_in_code_and_shortcuts() {
+        let input = &[
+            "[Item] and `let a = b[i][j];`",
+            "```rust",
+            "let a = b[i][j];",
+            "```",
+        ];
+        assert!(dangling(input).is_empty());
+    }
+}
@@ -1,27 +1,74 @@
-use std::io::Read;
-use std::path::Path;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
+mod alerts;
+mod comments;
 mod extract;
+mod fence;
+mod headings;
+mod links;
+mod options;
 mod process;
+mod rst;
 mod template;
+mod wrap;
 
-use config;
+use config::{self, Manifest};
+use log;
 
-/// Generates readme data from `source` file
+use self::fence::FenceTracker;
+pub use self::options::{Format, ReadmeOptions};
+pub use self::template::TEMPLATE_TAGS;
+
+/// Generates readme data from `sources` files
+///
+/// The docs of each source are concatenated in order, separated by a blank line, or by the
+/// `input_separator` of the options between blank lines. Optionally, a template can be used to
+/// render the output. The template can be any reader, independently of the type of the sources,
+/// like a file template with sources from stdin.
+///
+/// The crate information is read from the `Cargo.toml` in `project_root`, which can be missing if
+/// nothing from it is output, like with `add_title(false)` and no template. The `options`
+/// correspond to the command line options of `cargo readme`, see `ReadmeOptions`.
 ///
-/// Optionally, a template can be used to render the output
-pub fn generate_readme<T: Read>(
+/// ```no_run
+/// use std::fs::File;
+/// use std::path::Path;
+///
+/// use cargo_readme::ReadmeOptions;
+///
+/// let project_root = Path::new("path/to/my-crate");
+/// let source_path = project_root.join("src/lib.rs");
+/// let source = File::open(&source_path).unwrap();
+/// let options = ReadmeOptions::new()
+///     .source_paths(vec![Some(source_path)])
+///     .add_badges(false);
+/// let readme = cargo_readme::generate_readme(project_root, &mut [source], None, &options).unwrap();
+/// ```
+pub fn generate_readme<S: Read>(
     project_root: &Path,
-    source: &mut T,
-    template: Option<&mut T>,
-    add_title: bool,
-    add_badges: bool,
-    add_license: bool,
-    indent_headings: bool,
+    sources: &mut [S],
+    template: Option<&mut dyn Read>,
+    options: &ReadmeOptions,
 ) -> Result<String, String> {
-    let lines = extract::extract_docs(source).map_err(|e| format!("{}", e))?;
-
-    let readme = process::process_docs(lines, indent_headings).join("\n");
+    let separator = input_separator(&options.input_separator);
+    let mut lines = Vec::new();
+    for (index, source) in sources.iter_mut().enumerate() {
+        let source_path = options.source_paths.get(index).and_then(Option::as_ref);
+        let include = |path: &str| read_included_file(project_root, source_path, path);
+        let docs = extract::extract_docs(
+            source,
+            options.outer_docs,
+            options.include_modules,
+            &include,
+        )
+        .map_err(|e| format!("{}", e))?;
+        if !lines.is_empty() && !docs.is_empty() {
+            lines.extend(separator.iter().cloned());
+        }
+        lines.extend(docs);
+    }
 
     // get template from file
     let template = if let Some(template) = template {
@@ -30,14 +77,334 @@ pub fn generate_readme<T: Read>(
         None
     };
 
-    // get manifest from Cargo.toml
-    let cargo = config::get_manifest(project_root)?;
+    // without `Cargo.toml`, the crate information is empty, which is only an error if it is output
+    let cargo = if project_root.join("Cargo.toml").is_file()
+        || needs_crate_info(template.as_deref(), options)
+    {
+        config::get_manifest(project_root)?
+    } else {
+        Manifest::default()
+    };
+
+    for badge in &cargo.unknown_badges {
+        log::warn(&format!(
+            "Unknown badge `{}` in Cargo.toml is skipped",
+            badge
+        ));
+    }
+
+    // the text of the license is only used without template
+    let license_body = if options.license_body && options.add_license && template.is_none() {
+        read_license_body(project_root, &cargo)?
+    } else {
+        None
+    };
+
+    render_docs(
+        &lines.join("\n"),
+        template.as_deref(),
+        options,
+        &cargo,
+        license_body.as_deref(),
+    )
+}
+
+/// Get the lines inserted between the docs of two sources
+///
+/// A blank separator is a single blank line, otherwise the separator is between blank lines, so a
+/// `---` separator is a horizontal rule and not the underline of a heading.
+fn input_separator(separator: &str) -> Vec<String> {
+    if separator.trim().is_empty() {
+        return vec![String::new()];
+    }
+
+    let mut lines = vec![String::new()];
+    lines.extend(separator.trim_matches('\n').lines().map(str::to_owned));
+    lines.push(String::new());
+    lines
+}
+
+/// Is the crate information output, by the template or, without template, as the title, or used
+/// to rewrite links
+fn needs_crate_info(template: Option<&str>, options: &ReadmeOptions) -> bool {
+    options.expand_intra_links
+        || options.rewrite_relative_links
+        || match template {
+            Some(template) => template::uses_crate_info(template),
+            None => options.add_title,
+        }
+}
+
+/// Renders the readme from docs already extracted from the sources
+///
+/// `docs` is the markdown of the doc comments, without the comment markers, and `cargo` the crate
+/// information, like the one returned by `parse_manifest`. Nothing is read from the filesystem,
+/// so this can be used with docs from another source, like the JSON output of rustdoc.
+///
+/// ```
+/// use cargo_readme::ReadmeOptions;
+///
+/// let manifest = cargo_readme::parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+/// let options = ReadmeOptions::new().add_license(false);
+/// let readme = cargo_readme::render("Docs of my crate", None, &options, &manifest).unwrap();
+/// assert_eq!(readme, "# my-crate\n\nDocs of my crate\n");
+/// ```
+pub fn render(
+    docs: &str,
+    template: Option<&str>,
+    options: &ReadmeOptions,
+    cargo: &Manifest,
+) -> Result<String, String> {
+    render_docs(docs, template, options, cargo, None)
+}
+
+fn render_docs(
+    docs: &str,
+    template: Option<&str>,
+    options: &ReadmeOptions,
+    cargo: &Manifest,
+    license_body: Option<&str>,
+) -> Result<String, String> {
+    let mut lines: Vec<String> = docs.lines().map(|line| line.to_owned()).collect();
+    let docs_definitions = links::reference_definitions(&lines);
+
+    // no docs usually means the wrong source file was used
+    if !options.allow_empty && lines.iter().all(|line| line.trim().is_empty()) {
+        return Err("No doc comments found in the source file".to_owned());
+    }
+
+    if options.add_title && options.dedup_title {
+        remove_title(&mut lines, &cargo.name);
+    }
+
+    // a code block left open would swallow the rest of the readme, like the license
+    if let Some(delimiter) = unclosed_fence(&lines) {
+        let message = "A code block is not closed at the end of the docs";
+        if options.strict {
+            return Err(message.to_owned());
+        }
+        log::warn(&format!("{}, it is closed with `{}`", message, delimiter));
+        lines.push(delimiter);
+    }
+
+    let mut lines = process::process_docs(lines, options);
+
+    if options.strip_html_comments {
+        lines = comments::strip_html_comments(lines);
+    }
+
+    if !options.exclude_sections.is_empty() {
+        lines = headings::exclude_sections(lines, &options.exclude_sections);
+    }
+
+    if options.expand_intra_links {
+        lines = links::expand_intra_links(lines, &cargo.name);
+    }
+
+    if options.rewrite_relative_links {
+        match cargo.repository {
+            Some(ref repository) => lines = links::rewrite_relative_links(lines, repository),
+            None => log::warn(
+                "Relative links are not rewritten, there is no `repository` in Cargo.toml",
+            ),
+        }
+    }
+
+    if options.gfm_alerts {
+        lines = alerts::rewrite_alerts(lines);
+    }
+
+    if options.squeeze_blank_lines {
+        lines = squeeze_blanks(lines);
+    }
+
+    if let Some(width) = options.wrap {
+        lines = wrap::wrap_paragraphs(lines, width);
+    }
+
+    if options.add_anchors {
+        lines = headings::add_anchors(lines);
+    }
+
+    trim_blank_lines(&mut lines);
+
+    // definitions can be lost with the excluded sections or the docs before the readme markers
+    for id in links::dangling_references(&lines, &docs_definitions) {
+        log::warn(&format!("The link reference `[{}]` has no definition", id));
+    }
+
+    let readme = lines.join("\n");
+
+    let mut readme = template::render(
+        template.map(|template| template.to_owned()),
+        readme,
+        cargo,
+        options,
+        license_body,
+    )?;
+
+    if options.format == Format::Rst {
+        readme = rst::markdown_to_rst(&readme);
+    }
+
+    // make sure the result ends with exactly one new line
+    let len = readme.trim_end_matches('\n').len();
+    readme.truncate(len);
+    readme.push('\n');
+
+    Ok(readme)
+}
+
+/// Get the delimiter of the code block still open at the end of the docs, if any
+fn unclosed_fence(lines: &[String]) -> Option<String> {
+    let mut fence = FenceTracker::new();
+    for line in lines {
+        fence.is_code(line);
+    }
+    fence.open_delimiter().map(str::to_owned)
+}
+
+/// Read the text of the license files of the crate, separated by a blank line
+///
+/// `None` is returned if the crate has no license.
+fn read_license_body(project_root: &Path, cargo: &Manifest) -> Result<Option<String>, String> {
+    let files = config::project::find_license_files(project_root, cargo)?;
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let mut texts = Vec::new();
+    for file in files {
+        let text = fs::read_to_string(&file).map_err(|e| {
+            format!(
+                "Could not read license file '{}': {}",
+                config::project::display_path(&file),
+                e
+            )
+        })?;
+        texts.push(text.trim().to_owned());
+    }
+
+    Ok(Some(texts.join("\n\n")))
+}
+
+/// Get the files included in the docs of a source with `#![doc = include_str!("...")]`, relative to
+/// the source like when the readme is generated
+///
+/// A source which cannot be read includes no files.
+pub fn included_files(source_path: &Path) -> Vec<PathBuf> {
+    let dir = source_path.parent().unwrap_or_else(|| Path::new(""));
+
+    fs::read_to_string(source_path)
+        .map(|source| {
+            extract::included_paths(&source)
+                .iter()
+                .map(|path| dir.join(path))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read a file included in the docs with `include_str!`, relative to the source including it
+///
+/// A source including itself is an error, its code would end up in the readme.
+fn read_included_file(
+    project_root: &Path,
+    source_path: Option<&PathBuf>,
+    path: &str,
+) -> io::Result<String> {
+    let dir = source_path
+        .and_then(|source| source.parent())
+        .unwrap_or(project_root);
+    let included = dir.join(path);
+
+    if let Some(source) = source_path {
+        if let (Ok(a), Ok(b)) = (source.canonicalize(), included.canonicalize()) {
+            if a == b {
+                return Err(io::Error::other(format!(
+                    "'{}' includes itself in its docs",
+                    config::project::display_path(source)
+                )));
+            }
+        }
+    }
+
+    fs::read_to_string(&included).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "Could not read file '{}' included in the docs: {}",
+                config::project::display_path(&included),
+                e
+            ),
+        )
+    })
+}
+
+/// Remove the first heading of the docs if it is the crate name, since the title is added anyway
+///
+/// The blank lines after the removed heading are also removed. Like in crate names, `-` and `_`
+/// are considered equal.
+fn remove_title(lines: &mut Vec<String>, crate_name: &str) {
+    let normalize = |name: &str| name.trim().replace('-', "_");
+
+    let first = match lines.iter().position(|line| !line.trim().is_empty()) {
+        Some(first) => first,
+        None => return,
+    };
+
+    let is_title =
+        lines[first].starts_with("# ") && normalize(&lines[first][2..]) == normalize(crate_name);
+
+    if is_title {
+        let end = lines[first + 1..]
+            .iter()
+            .position(|line| !line.trim().is_empty())
+            .map_or(lines.len(), |pos| first + 1 + pos);
+        lines.drain(first..end);
+    }
+}
+
+/// Remove the blank lines at the start and at the end of the docs
+///
+/// Blank lines in between are kept, since they are significant in markdown.
+fn trim_blank_lines(lines: &mut Vec<String>) {
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let start = lines
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .unwrap_or(lines.len());
+    lines.drain(..start);
+}
+
+/// Collapse runs of blank lines into a single blank line
+///
+/// Blank lines inside code blocks are part of the code, so they are kept as they are.
+fn squeeze_blanks(lines: Vec<String>) -> Vec<String> {
+    let mut fence = FenceTracker::new();
+    let mut previous_blank = false;
+
+    lines
+        .into_iter()
+        .filter(|line| {
+            if fence.is_code(line) {
+                previous_blank = false;
+                return true;
+            }
 
-    template::render(template, readme, &cargo, add_title, add_badges, add_license)
+            let blank = line.trim().is_empty();
+            let keep = !(blank && previous_blank);
+            previous_blank = blank;
+            keep
+        })
+        .collect()
 }
 
 /// Load a template String from a file
-fn get_template_string<T: Read>(template: &mut T) -> Result<String, String> {
+fn get_template_string(template: &mut dyn Read) -> Result<String, String> {
     let mut template_string = String::new();
     match template.read_to_string(&mut template_string) {
         Err(e) => return Err(format!("Error: {}", e)),
@@ -46,3 +413,381 @@ fn get_template_string<T: Read>(template: &mut T) -> Result<String, String> {
 
     Ok(template_string)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Cursor;
+    use std::path::Path;
+
+    use super::{
+        generate_readme, remove_title, render, squeeze_blanks, trim_blank_lines, ReadmeOptions,
+    };
+    use config::parse_manifest;
+
+    #[test]
+    fn generate_readme_ends_with_single_new_line() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let source = File::open(project_root.join("src/lib.rs")).unwrap();
+
+        let result =
+            generate_readme(&project_root, &mut [source], None, &ReadmeOptions::new()).unwrap();
+
+        assert!(result.ends_with("\n"));
+        assert!(!result.ends_with("\n\n"));
+    }
+
+    #[test]
+    fn generate_readme_add_anchors() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let source = Cursor::new("//! # Usage\n//!\n//! Text\n//!\n//! ## Usage".as_bytes());
+
+        let result = generate_readme(
+            &project_root,
+            &mut [source],
+            None,
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false)
+                .add_anchors(true),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "<a name=\"usage\"></a>\n## Usage\n\nText\n\n<a name=\"usage-1\"></a>\n### Usage\n",
+            result
+        );
+    }
+
+    #[test]
+    fn generate_readme_from_multiple_sources() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let mut sources = [
+            Cursor::new("//! # First\n//!\n//! From lib.rs\n\nfn main() {}".as_bytes()),
+            Cursor::new("fn no_docs() {}".as_bytes()),
+            Cursor::new("//! # Second\n//!\n//! From overview.rs".as_bytes()),
+        ];
+
+        let result = generate_readme(
+            &project_root,
+            &mut sources,
+            None,
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "## First\n\nFrom lib.rs\n\n## Second\n\nFrom overview.rs\n",
+            result
+        );
+    }
+
+    #[test]
+    fn generate_readme_without_surrounding_blank_lines() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let source =
+            Cursor::new("//!\n//!\n//! First\n//!\n//!\n//! Second\n//!\n//!\n".as_bytes());
+
+        let result = generate_readme(
+            &project_root,
+            &mut [source],
+            None,
+            &ReadmeOptions::new().add_badges(false),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "# readme-test\n\nFirst\n\n\nSecond\n\nLicense: MIT\n",
+            result
+        );
+    }
+
+    #[test]
+    fn generate_readme_squeeze_blank_lines() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let source = Cursor::new(
+            "//! First\n//!\n//!\n//!\n//! Second\n//!\n//! ```\n//! a\n//!\n//!\n//!\n//! b\n//! ```"
+                .as_bytes(),
+        );
+
+        let result = generate_readme(
+            &project_root,
+            &mut [source],
+            None,
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false)
+                .squeeze_blank_lines(true),
+        )
+        .unwrap();
+
+        assert_eq!("First\n\nSecond\n\n```rust\na\n\n\n\nb\n```\n", result);
+    }
+
+    #[test]
+    fn generate_readme_strip_html_comments() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let source = Cursor::new(
+            "//! First <!-- inline -->\n//!\n//! <!--\n//! multi-line\n//! -->\n//!\n\
+             //! ```html\n//! <!-- kept -->\n//! ```"
+                .as_bytes(),
+        );
+
+        let result = generate_readme(
+            &project_root,
+            &mut [source],
+            None,
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false)
+                .strip_html_comments(true),
+        )
+        .unwrap();
+
+        assert_eq!("First\n\n```html\n<!-- kept -->\n```\n", result);
+    }
+
+    #[test]
+    fn generate_readme_hidden_lines() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let source = Cursor::new(
+            "//! ```\n//! # fn main() {\n//! ## comment\n//! #[derive(Debug)]\n\
+             //! struct Unit;\n//! # }\n//! ```"
+                .as_bytes(),
+        );
+
+        let result = generate_readme(
+            &project_root,
+            &mut [source],
+            None,
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "```rust\n# comment\n#[derive(Debug)]\nstruct Unit;\n```\n",
+            result
+        );
+    }
+
+    #[test]
+    fn generate_readme_exclude_sections() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let source = Cursor::new(
+            "//! Docs\n//!\n//! # Usage\n//!\n//! Use it\n//!\n//! # Internal\n//!\n\
+             //! ## Details\n//!\n//! Not for the readme\n//!\n//! # Examples\n//!\n//! Example"
+                .as_bytes(),
+        );
+
+        let result = generate_readme(
+            &project_root,
+            &mut [source],
+            None,
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false)
+                .exclude_sections(vec!["Internal".to_owned()]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "Docs\n\n## Usage\n\nUse it\n\n## Examples\n\nExample\n",
+            result
+        );
+    }
+
+    #[test]
+    fn input_separators() {
+        assert_eq!(super::input_separator("\n"), &[""]);
+        assert_eq!(super::input_separator(""), &[""]);
+        assert_eq!(super::input_separator("---"), &["", "---", ""]);
+        assert_eq!(super::input_separator("\n# Part\n\n"), &["", "# Part", ""]);
+    }
+
+    #[test]
+    fn generate_readme_with_many_lines() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let docs: Vec<String> = (0..5000).map(|i| format!("line {}", i)).collect();
+        let source: String = docs.iter().map(|line| format!("//! {}\n", line)).collect();
+
+        let result = generate_readme(
+            &project_root,
+            &mut [Cursor::new(source.as_bytes())],
+            None,
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false),
+        )
+        .unwrap();
+
+        assert_eq!(format!("{}\n", docs.join("\n")), result);
+    }
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn remove_title_matching_crate_name() {
+        let mut docs = lines(&["", "# my_crate", "", "Some docs", "", "# Other heading"]);
+        remove_title(&mut docs, "my-crate");
+        assert_eq!(docs, &["", "Some docs", "", "# Other heading"]);
+    }
+
+    #[test]
+    fn keep_title_not_matching_crate_name() {
+        let input = &["# Introduction", "", "Some docs"];
+        let mut docs = lines(input);
+        remove_title(&mut docs, "my-crate");
+        assert_eq!(docs, input);
+    }
+
+    #[test]
+    fn keep_title_not_first_line() {
+        let input = &["Some docs", "", "# my-crate"];
+        let mut docs = lines(input);
+        remove_title(&mut docs, "my-crate");
+        assert_eq!(docs, input);
+    }
+
+    #[test]
+    fn keep_title_not_level_1() {
+        let input = &["## my-crate", "", "Some docs"];
+        let mut docs = lines(input);
+        remove_title(&mut docs, "my-crate");
+        assert_eq!(docs, input);
+    }
+
+    #[test]
+    fn trim_blank_lines_at_start_and_end() {
+        let mut docs = lines(&["", "  ", "First", "", "", "Second", "", ""]);
+        trim_blank_lines(&mut docs);
+        assert_eq!(docs, &["First", "", "", "Second"]);
+    }
+
+    #[test]
+    fn squeeze_blanks_outside_code_blocks() {
+        let docs = lines(&[
+            "First", "", "", "", "Second", "```", "", "", "```", "", "", "Third",
+        ]);
+        assert_eq!(
+            squeeze_blanks(docs),
+            &["First", "", "Second", "```", "", "", "```", "", "Third"]
+        );
+    }
+
+    #[test]
+    fn trim_blank_lines_only() {
+        let mut docs = lines(&["", ""]);
+        trim_blank_lines(&mut docs);
+        assert!(docs.is_empty());
+    }
+
+    #[test]
+    fn render_docs_with_title_and_license() {
+        let manifest = parse_manifest("[package]\nname = \"my-crate\"\nlicense = \"MIT\"").unwrap();
+        let docs = "Docs\n\n# Usage\n\n```\nlet x = 1;\n# hidden();\n```";
+
+        let result = render(docs, None, &ReadmeOptions::new(), &manifest).unwrap();
+
+        assert_eq!(
+            result,
+            "# my-crate\n\nDocs\n\n## Usage\n\n```rust\nlet x = 1;\n```\n\nLicense: MIT\n"
+        );
+    }
+
+    #[test]
+    fn render_docs_with_template() {
+        let manifest =
+            parse_manifest("[package]\nname = \"my-crate\"\nversion = \"1.0.0\"").unwrap();
+        let template = "# {{crate}} {{version}}\n\n{{readme}}";
+
+        let result = render("Docs", Some(template), &ReadmeOptions::new(), &manifest).unwrap();
+
+        assert_eq!(result, "# my-crate 1.0.0\n\nDocs\n");
+    }
+
+    #[test]
+    fn render_empty_docs_should_fail() {
+        let manifest = parse_manifest("[package]\nname = \"my-crate\"").unwrap();
+
+        let result = render("\n\n", None, &ReadmeOptions::new(), &manifest);
+        assert_eq!(
+            result,
+            Err("No doc comments found in the source file".to_owned())
+        );
+
+        let result = render("", None, &ReadmeOptions::new().allow_empty(true), &manifest);
+        assert_eq!(result, Ok("# my-crate\n".to_owned()));
+    }
+
+    #[test]
+    fn render_unclosed_code_block() {
+        let manifest = parse_manifest("[package]\nname = \"my-crate\"\nlicense = \"MIT\"").unwrap();
+        let docs = "Docs\n\n```\nlet x = 1;\n```\n\n```text\nnot closed";
+
+        let result = render(docs, None, &ReadmeOptions::new(), &manifest);
+        assert_eq!(
+            result,
+            Ok("# my-crate\n\nDocs\n\n```rust\nlet x = 1;\n```\n\n\
+                ```text\nnot closed\n```\n\nLicense: MIT\n"
+                .to_owned())
+        );
+
+        let result = render(docs, None, &ReadmeOptions::new().strict(true), &manifest);
+        assert_eq!(
+            result,
+            Err("A code block is not closed at the end of the docs".to_owned())
+        );
+    }
+
+    #[test]
+    fn generate_readme_with_template_of_another_type() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test-project");
+        let source = File::open(project_root.join("src/single_line.rs")).unwrap();
+        let mut template = Cursor::new("# {{crate}}\n\n{{readme}}".as_bytes());
+
+        let result = generate_readme(
+            &project_root,
+            &mut [source],
+            Some(&mut template),
+            &ReadmeOptions::new(),
+        )
+        .unwrap();
+
+        assert_eq!("# readme-test\n\nTest crate for cargo-readme\n", result);
+    }
+
+    #[test]
+    fn generate_readme_without_manifest() {
+        // the `tests` directory has no `Cargo.toml`
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let source = Cursor::new("//! Docs without crate".as_bytes());
+        let options = ReadmeOptions::new().add_title(false);
+
+        let result = generate_readme(&project_root, &mut [source], None, &options);
+
+        assert_eq!(Ok("Docs without crate\n".to_owned()), result);
+    }
+
+    #[test]
+    fn generate_readme_without_manifest_with_title_should_fail() {
+        let project_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let source = Cursor::new("//! Docs without crate".as_bytes());
+
+        let result = generate_readme(&project_root, &mut [source], None, &ReadmeOptions::new());
+
+        assert!(result.unwrap_err().starts_with("Could not read Cargo.toml"));
+    }
+}
@@ -0,0 +1,323 @@
+//! Options of the readme generation
+
+use std::path::PathBuf;
+
+/// Format of the generated readme
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Markdown,
+    /// reStructuredText, converted from the markdown of the docs
+    Rst,
+}
+
+/// Options of `generate_readme`
+///
+/// They correspond to the command line options of `cargo readme`, and the defaults are the same
+/// as on the command line. The options are set with builder methods:
+///
+/// ```
+/// use cargo_readme::ReadmeOptions;
+///
+/// let options = ReadmeOptions::new()
+///     .add_badges(false)
+///     .heading_indent(2)
+///     .wrap(Some(80));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReadmeOptions {
+    pub(crate) source_paths: Vec<Option<PathBuf>>,
+    pub(crate) input_separator: String,
+    pub(crate) add_title: bool,
+    pub(crate) title_format: String,
+    pub(crate) title_case: bool,
+    pub(crate) dedup_title: bool,
+    pub(crate) add_badges: bool,
+    pub(crate) add_license: bool,
+    pub(crate) license_prose: bool,
+    pub(crate) license_heading: bool,
+    pub(crate) license_body: bool,
+    pub(crate) license_prefix: String,
+    pub(crate) license_link: bool,
+    pub(crate) append_metadata: bool,
+    pub(crate) heading_indent: usize,
+    pub(crate) no_indent_headings: Vec<String>,
+    pub(crate) strip_hidden: bool,
+    pub(crate) fence_indented: bool,
+    pub(crate) rust_fence_label: String,
+    pub(crate) expand_intra_links: bool,
+    pub(crate) rewrite_relative_links: bool,
+    pub(crate) gfm_alerts: bool,
+    pub(crate) strip_html_comments: bool,
+    pub(crate) exclude_sections: Vec<String>,
+    pub(crate) add_anchors: bool,
+    pub(crate) squeeze_blank_lines: bool,
+    pub(crate) wrap: Option<usize>,
+    pub(crate) outer_docs: bool,
+    pub(crate) include_modules: bool,
+    pub(crate) allow_empty: bool,
+    pub(crate) date: Option<String>,
+    pub(crate) strict: bool,
+    pub(crate) format: Format,
+}
+
+impl Default for ReadmeOptions {
+    fn default() -> Self {
+        ReadmeOptions {
+            source_paths: Vec::new(),
+            input_separator: "\n".to_owned(),
+            add_title: true,
+            title_format: "# {name}\n\n".to_owned(),
+            title_case: false,
+            dedup_title: false,
+            add_badges: true,
+            add_license: true,
+            license_prose: false,
+            license_heading: false,
+            license_body: false,
+            license_prefix: "License: ".to_owned(),
+            license_link: false,
+            append_metadata: false,
+            heading_indent: 1,
+            no_indent_headings: Vec::new(),
+            strip_hidden: true,
+            fence_indented: false,
+            rust_fence_label: "rust".to_owned(),
+            expand_intra_links: false,
+            rewrite_relative_links: false,
+            gfm_alerts: false,
+            strip_html_comments: false,
+            exclude_sections: Vec::new(),
+            add_anchors: false,
+            squeeze_blank_lines: false,
+            wrap: None,
+            outer_docs: false,
+            include_modules: false,
+            allow_empty: false,
+            date: None,
+            strict: false,
+            format: Format::Markdown,
+        }
+    }
+}
+
+impl ReadmeOptions {
+    pub fn new() -> Self {
+        ReadmeOptions::default()
+    }
+
+    /// Paths of the sources, in the same order, used to resolve the files included with
+    /// `#![doc = include_str!("...")]`
+    ///
+    /// When a source has no path, like stdin, its includes are resolved against the project root.
+    pub fn source_paths(mut self, source_paths: Vec<Option<PathBuf>>) -> Self {
+        self.source_paths = source_paths;
+        self
+    }
+
+    /// Text between the docs of two sources, "\n" by default for a blank line
+    ///
+    /// A separator which is not blank, like "---", is written between blank lines.
+    pub fn input_separator(mut self, input_separator: &str) -> Self {
+        self.input_separator = input_separator.to_owned();
+        self
+    }
+
+    /// Prepend the crate name as title when no template is used
+    pub fn add_title(mut self, add_title: bool) -> Self {
+        self.add_title = add_title;
+        self
+    }
+
+    /// Title with a `{name}` placeholder for the crate name, "# {name}\n\n" by default
+    pub fn title_format(mut self, title_format: &str) -> Self {
+        self.title_format = title_format.to_owned();
+        self
+    }
+
+    /// Write the crate name as words in title case, like "My Crate" for "my_crate", in the title
+    /// and in the `{{crate}}` tag
+    pub fn title_case(mut self, title_case: bool) -> Self {
+        self.title_case = title_case;
+        self
+    }
+
+    /// Remove the first heading of the docs if it is the crate name
+    pub fn dedup_title(mut self, dedup_title: bool) -> Self {
+        self.dedup_title = dedup_title;
+        self
+    }
+
+    /// Prepend the badges when no template is used
+    pub fn add_badges(mut self, add_badges: bool) -> Self {
+        self.add_badges = add_badges;
+        self
+    }
+
+    /// Append the license when no template is used
+    pub fn add_license(mut self, add_license: bool) -> Self {
+        self.add_license = add_license;
+        self
+    }
+
+    /// Write the license as a sentence, like "Licensed under either of MIT or Apache-2.0"
+    pub fn license_prose(mut self, license_prose: bool) -> Self {
+        self.license_prose = license_prose;
+        self
+    }
+
+    /// Write the license in a "License" section instead of a single line
+    pub fn license_heading(mut self, license_heading: bool) -> Self {
+        self.license_heading = license_heading;
+        self
+    }
+
+    /// Write the text of the license files in the "License" section instead of the license name
+    ///
+    /// The files are found in the project root, see `project::find_license_files`, so this is not
+    /// used by `render`, which has no access to the filesystem.
+    pub fn license_body(mut self, license_body: bool) -> Self {
+        self.license_body = license_body;
+        self
+    }
+
+    /// Text written before the license line, "License: " by default
+    ///
+    /// The prefix is not written when the license is written as a sentence with `license_prose`.
+    pub fn license_prefix(mut self, license_prefix: &str) -> Self {
+        self.license_prefix = license_prefix.to_owned();
+        self
+    }
+
+    /// Write the SPDX ids of the license as links to their page on spdx.org, like
+    /// "[MIT](https://spdx.org/licenses/MIT.html)"
+    ///
+    /// A license which is not a SPDX expression is written as is.
+    pub fn license_link(mut self, license_link: bool) -> Self {
+        self.license_link = license_link;
+        self
+    }
+
+    /// Append the keywords and categories when no template is used
+    pub fn append_metadata(mut self, append_metadata: bool) -> Self {
+        self.append_metadata = append_metadata;
+        self
+    }
+
+    /// Number of levels added to the headings in the docs, 1 by default
+    pub fn heading_indent(mut self, heading_indent: usize) -> Self {
+        self.heading_indent = heading_indent;
+        self
+    }
+
+    /// Headings left at their level by `heading_indent`, like "Examples", compared
+    /// case-insensitively
+    pub fn no_indent_headings(mut self, no_indent_headings: Vec<String>) -> Self {
+        self.no_indent_headings = no_indent_headings;
+        self
+    }
+
+    /// Remove hidden lines from rust code blocks
+    pub fn strip_hidden(mut self, strip_hidden: bool) -> Self {
+        self.strip_hidden = strip_hidden;
+        self
+    }
+
+    /// Convert indented code blocks into fenced rust code blocks
+    pub fn fence_indented(mut self, fence_indented: bool) -> Self {
+        self.fence_indented = fence_indented;
+        self
+    }
+
+    /// Language of rust code blocks, "rust" by default, or empty for none
+    pub fn rust_fence_label(mut self, rust_fence_label: &str) -> Self {
+        self.rust_fence_label = rust_fence_label.to_owned();
+        self
+    }
+
+    /// Rewrite intra-doc links to point to docs.rs, to the page of the item if its kind is given
+    /// by a disambiguator, like "struct@Item", or else to a search for the item
+    pub fn expand_intra_links(mut self, expand_intra_links: bool) -> Self {
+        self.expand_intra_links = expand_intra_links;
+        self
+    }
+
+    /// Rewrite the links to files of the repository, like "docs/guide.md", into absolute urls,
+    /// using the `repository` of `Cargo.toml`
+    pub fn rewrite_relative_links(mut self, rewrite_relative_links: bool) -> Self {
+        self.rewrite_relative_links = rewrite_relative_links;
+        self
+    }
+
+    /// Rewrite the blockquotes starting with "Note:", "Warning:" and the like into GitHub alerts
+    pub fn gfm_alerts(mut self, gfm_alerts: bool) -> Self {
+        self.gfm_alerts = gfm_alerts;
+        self
+    }
+
+    /// Remove the HTML comments, like `<!-- ... -->`, outside of code blocks
+    pub fn strip_html_comments(mut self, strip_html_comments: bool) -> Self {
+        self.strip_html_comments = strip_html_comments;
+        self
+    }
+
+    /// Remove the sections with these headings, compared case-insensitively, with their
+    /// subsections
+    pub fn exclude_sections(mut self, exclude_sections: Vec<String>) -> Self {
+        self.exclude_sections = exclude_sections;
+        self
+    }
+
+    /// Add an html anchor before each heading, named like the ones GitHub generates
+    pub fn add_anchors(mut self, add_anchors: bool) -> Self {
+        self.add_anchors = add_anchors;
+        self
+    }
+
+    /// Collapse consecutive blank lines outside of code blocks into one
+    pub fn squeeze_blank_lines(mut self, squeeze_blank_lines: bool) -> Self {
+        self.squeeze_blank_lines = squeeze_blank_lines;
+        self
+    }
+
+    /// Reflow the prose paragraphs to the given width
+    pub fn wrap(mut self, wrap: Option<usize>) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Also read `///` item docs, not only `//!` crate docs
+    pub fn outer_docs(mut self, outer_docs: bool) -> Self {
+        self.outer_docs = outer_docs;
+        self
+    }
+
+    /// Also read the `//!` docs of the modules declared inline, each under a heading with its name
+    pub fn include_modules(mut self, include_modules: bool) -> Self {
+        self.include_modules = include_modules;
+        self
+    }
+
+    /// Accept a source without doc comments
+    pub fn allow_empty(mut self, allow_empty: bool) -> Self {
+        self.allow_empty = allow_empty;
+        self
+    }
+
+    /// Date substituted for the `{{date}}` tag of the template, which is kept as it is when `None`
+    pub fn date(mut self, date: Option<String>) -> Self {
+        self.date = date;
+        self
+    }
+
+    /// Make unknown template tags and code blocks left open an error instead of a warning
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Format of the readme, markdown by default
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+}
@@ -1,74 +1,236 @@
 //! Transform code blocks from rustdoc into markdown
 //!
 //! Rewrite code block start tags, changing rustdoc into equivalent in markdown:
-//! - "```", "```no_run", "```ignore" and "```should_panic" are converted to "```rust"
-//! - markdown heading are indentend to be one level lower, so the crate name is at the top level
+//! - "```", "```no_run", "```ignore", "```should_panic", "```edition2021" and other rustdoc
+//!   attributes, alone or in a comma separated list, are converted to "```rust", or to another
+//!   label, like "```rs", and so is "```rust" followed by any attribute, like "```rust,custom"
+//! - code blocks in other languages, like "```text" or "```json", are kept as they are
+//! - markdown heading are indentend to be lower by a number of levels, by default one, so the crate
+//!   name is at the top level, except for the headings given to be left at their level
+//! - indented code blocks are kept verbatim, except for hidden lines, since rustdoc also treats
+//!   them as rust code, or optionally converted to "```rust" blocks
 
+use std::cmp;
 use std::iter::{IntoIterator, Iterator};
 
 use regex::Regex;
 
+use super::fence::{code_block_start, is_code_block_end};
+use super::headings;
+use super::ReadmeOptions;
+
 lazy_static!{
-    // Is this code block rust?
-    static ref RE_CODE_RUST: Regex = Regex::new(r"^(?P<delimiter>`{3,4}|~{3,4})(?:rust|(?:(?:rust,)?(?:no_run|ignore|should_panic)))?$").unwrap();
-    // Is this code block just text?
-    static ref RE_CODE_TEXT: Regex = Regex::new(r"^(?P<delimiter>`{3,4}|~{3,4})text$").unwrap();
-    // Is this code block a language other than rust?
-    static ref RE_CODE_OTHER: Regex = Regex::new(r"^(?P<delimiter>`{3,4}|~{3,4})\w[\w,\+]*$").unwrap();
+    // Is this line a list item? Indented lines after it are a continuation, not code
+    static ref RE_LIST_ITEM: Regex = Regex::new(r"^ {0,3}(?:[-*+]|\d+[.)])(?: |$)").unwrap();
 }
 
 /// Process and concatenate the doc lines into a single String
 ///
 /// The processing transforms doc tests into regular rust code blocks and optionally indent the
-/// markdown headings in order to leave the top heading to the crate name
+/// markdown headings by the `heading_indent` of the options in order to leave the top heading to
+/// the crate name
+///
+/// The headings whose text is in `no_indent_headings`, compared case-insensitively, are left at
+/// their level.
+///
+/// Rust code blocks are labeled with `rust_fence_label`, which can be empty to leave them without
+/// a language.
 pub fn process_docs<S: Into<String>, L: Into<Vec<S>>>(
     lines: L,
-    indent_headings: bool,
+    options: &ReadmeOptions,
 ) -> Vec<String> {
-    lines.into().into_iter().process_docs(indent_headings)
+    lines.into().into_iter().process_docs(options)
 }
 
 pub struct Processor {
     section: Section,
-    indent_headings: bool,
+    heading_indent: usize,
+    // lowercase text of the headings left at their level
+    no_indent_headings: Vec<String>,
+    strip_hidden: bool,
     delimiter: Option<String>,
+    // an indented code block can only start after a blank line or a heading, but not in a list
+    indented_code_allowed: bool,
+    in_list: bool,
+    fence_indented: bool,
+    blank_lines: Vec<String>,
+    rust_fence_label: String,
 }
 
 impl Processor {
-    pub fn new(indent_headings: bool) -> Self {
+    pub fn new(options: &ReadmeOptions) -> Self {
         Processor {
             section: Section::None,
-            indent_headings: indent_headings,
+            heading_indent: options.heading_indent,
+            no_indent_headings: options
+                .no_indent_headings
+                .iter()
+                .map(|heading| heading.trim().to_lowercase())
+                .collect(),
+            strip_hidden: options.strip_hidden,
             delimiter: None,
+            indented_code_allowed: true,
+            in_list: false,
+            fence_indented: options.fence_indented,
+            blank_lines: Vec::new(),
+            rust_fence_label: options.rust_fence_label.clone(),
+        }
+    }
+
+    /// Process a line and return the lines to output
+    ///
+    /// More than one line can be returned when fences are added around indented code blocks.
+    pub fn process_line(&mut self, line: String) -> Vec<String> {
+        let mut output = Vec::new();
+
+        if self.section == Section::CodeIndented {
+            if is_blank(&line) {
+                if self.fence_indented {
+                    // blank lines at the end of the block go after the closing fence
+                    self.blank_lines.push(String::new());
+                } else {
+                    output.push(line);
+                }
+                return output;
+            }
+
+            if !is_indented(&line) {
+                self.section = Section::None;
+                output.extend(self.close_indented_code());
+            }
+        } else if self.section == Section::None
+            && self.indented_code_allowed
+            && !self.in_list
+            && !is_blank(&line)
+            && is_indented(&line)
+        {
+            self.section = Section::CodeIndented;
+            if self.fence_indented {
+                output.push(format!("```{}", self.rust_fence_label));
+            }
+        }
+
+        if self.section == Section::CodeIndented {
+            if let Some(line) = self.process_indented_code_line(line) {
+                // blank lines are kept when they are not at the end of the block
+                output.append(&mut self.blank_lines);
+                output.push(line);
+            }
+        } else {
+            output.extend(self.process_markdown_line(line));
+        }
+
+        output
+    }
+
+    /// Finish processing, closing the indented code block still open at the end of the docs
+    pub fn finish(&mut self) -> Vec<String> {
+        if self.section == Section::CodeIndented {
+            self.section = Section::None;
+            return self.close_indented_code();
         }
+
+        Vec::new()
     }
 
-    pub fn process_line(&mut self, mut line: String) -> Option<String> {
-        // Skip lines that should be hidden in docs
-        if self.section == Section::CodeRust && line.starts_with("# ") {
-            return None;
+    fn close_indented_code(&mut self) -> Vec<String> {
+        let mut output = Vec::new();
+        if self.fence_indented {
+            output.push("```".to_owned());
         }
+        output.append(&mut self.blank_lines);
+        output
+    }
+
+    /// Indented code is kept as is, only hidden lines are handled
+    ///
+    /// When the block is fenced, the indentation is removed.
+    fn process_indented_code_line(&self, line: String) -> Option<String> {
+        let mut line = self.process_rust_line(line)?;
+
+        if self.fence_indented {
+            let width = if line.starts_with('\t') { 1 } else { 4 };
+            line.replace_range(..width, "");
+        }
+
+        Some(line)
+    }
+
+    /// Skip lines that should be hidden in docs, or just remove the marker if they are kept
+    ///
+    /// Like in rustdoc, a line is hidden if it starts with "# " or is a lone "#", after the
+    /// indentation. A line starting with "##" is an escaped "#" and is not hidden, and neither are
+    /// attributes, like "#[derive(Debug)]".
+    fn process_rust_line(&self, mut line: String) -> Option<String> {
+        let indent = line.len() - line.trim_start().len();
+        let code = line[indent..].to_owned();
+
+        if code == "#" || code.starts_with("# ") || code.starts_with("#\t") {
+            if self.strip_hidden {
+                return None;
+            }
+            line.replace_range(indent..(indent + 2).min(line.len()), "");
+        } else if code.starts_with("##") {
+            line.remove(indent);
+        }
+
+        Some(line)
+    }
 
-        // indent heading when outside code
-        if self.indent_headings && self.section == Section::None && line.starts_with("#") {
-            line.insert(0, '#');
+    /// Is the line a heading to leave at its level
+    fn is_no_indent_heading(&self, line: &str) -> bool {
+        headings::parse_heading(line).is_some_and(|(_, text)| {
+            self.no_indent_headings
+                .contains(&text.trim().to_lowercase())
+        })
+    }
+
+    fn process_markdown_line(&mut self, mut line: String) -> Option<String> {
+        if self.section == Section::None {
+            self.indented_code_allowed = is_blank(&line) || line.starts_with("#");
+            if !is_blank(&line) && !is_indented(&line) {
+                self.in_list = RE_LIST_ITEM.is_match(&line);
+            }
+        }
+
+        if self.section == Section::CodeRust && line.trim_start().starts_with('#') {
+            return self.process_rust_line(line);
+        }
+
+        // indent heading when outside code, up to the deepest level, so "######" stays a heading
+        if self.heading_indent > 0 && self.section == Section::None && line.starts_with("#") {
+            if !self.is_no_indent_heading(&line) {
+                if let Some((level, _)) = headings::parse_heading(&line) {
+                    let indent = cmp::min(level + self.heading_indent, 6) - level;
+                    line.insert_str(0, &"#".repeat(indent));
+                }
+            }
         } else if self.section == Section::None {
             let l = line.clone();
-            if let Some(cap) = RE_CODE_RUST.captures(&l) {
-                self.section = Section::CodeRust;
-                self.delimiter = cap.name("delimiter").map(|x| x.as_str().to_owned());
-                line = format!("{}rust", self.delimiter.as_ref().unwrap());
-            } else if let Some(cap) = RE_CODE_TEXT.captures(&l) {
-                self.section = Section::CodeOther;
-                self.delimiter = cap.name("delimiter").map(|x| x.as_str().to_owned());
-                line = self.delimiter.clone().unwrap();
-            } else if let Some(cap) = RE_CODE_OTHER.captures(&l) {
-                self.section = Section::CodeOther;
-                self.delimiter = cap.name("delimiter").map(|x| x.as_str().to_owned());
+            if let Some(cap) = code_block_start(&l) {
+                let delimiter = cap["delimiter"].to_owned();
+                match code_language(&cap["info"]) {
+                    None => {
+                        self.section = Section::CodeRust;
+                        line = format!("{}{}{}", &cap["indent"], delimiter, self.rust_fence_label);
+                    }
+                    Some(_) => {
+                        // other languages, including "text", are kept as they are, with the
+                        // whole info string, like "python title=\"example.py\""
+                        self.section = Section::CodeOther;
+                    }
+                }
+                self.delimiter = Some(delimiter);
             }
-        } else if self.section != Section::None && Some(&line) == self.delimiter.as_ref() {
+        } else if self.section != Section::None
+            && self
+                .delimiter
+                .as_ref()
+                .is_some_and(|delimiter| is_code_block_end(&line, delimiter))
+        {
             self.section = Section::None;
-            line = self.delimiter.take().unwrap_or("```".to_owned());
+            self.indented_code_allowed = true;
+            self.delimiter = None;
         }
 
         Some(line)
@@ -79,18 +241,65 @@ impl Processor {
 enum Section {
     CodeRust,
     CodeOther,
+    CodeIndented,
     None,
 }
 
+/// Get the language of a code block from its info string, like "rust,no_run" or "python"
+///
+/// Like in rustdoc, a code block is rust unless it names another language, so `None` is returned
+/// if the info string is empty or only has rustdoc attributes. When it starts with "rust", the
+/// other tokens are attributes, even the ones not known here, like those of newer rustdoc versions.
+fn code_language(info: &str) -> Option<&str> {
+    let mut tokens = info
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .peekable();
+
+    if tokens.peek() == Some(&"rust") {
+        return None;
+    }
+
+    tokens.find(|token| !is_rustdoc_attribute(token))
+}
+
+/// Is this token from a code block info string a rustdoc attribute?
+fn is_rustdoc_attribute(token: &str) -> bool {
+    match token {
+        "rust" | "no_run" | "ignore" | "should_panic" | "compile_fail" | "test_harness"
+        | "allow_fail" | "standalone_crate" => true,
+        // error codes, like "E0308", are used with "compile_fail"
+        _ => {
+            token.starts_with("edition")
+                || token.starts_with("ignore-")
+                || (token.len() == 5
+                    && token.starts_with('E')
+                    && token[1..].chars().all(|c| c.is_ascii_digit()))
+        }
+    }
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Is the line indented enough to be part of an indented code block?
+fn is_indented(line: &str) -> bool {
+    line.starts_with("    ") || line.starts_with("\t")
+}
+
 pub trait DocProcess<S: Into<String>> {
-    fn process_docs(self, indent_headings: bool) -> Vec<String>
+    fn process_docs(self, options: &ReadmeOptions) -> Vec<String>
     where
         Self: Sized + Iterator<Item = S>,
     {
-        let mut p = Processor::new(indent_headings);
-        self.into_iter()
-            .filter_map(|line| p.process_line(line.into()))
-            .collect()
+        let mut p = Processor::new(options);
+        let mut result: Vec<String> = self
+            .into_iter()
+            .flat_map(|line| p.process_line(line.into()))
+            .collect();
+        result.extend(p.finish());
+        result
     }
 }
 
@@ -99,6 +308,7 @@ impl<S: Into<String>, I: Iterator<Item = S>> DocProcess<S> for I {}
 #[cfg(test)]
 mod tests {
     use super::process_docs;
+    use readme::ReadmeOptions;
 
     const INPUT_HIDDEN_LINE: &[&str] = &[
         "```",
@@ -113,10 +323,101 @@ mod tests {
 
     #[test]
     fn hide_line_in_rust_code_block() {
-        let result = process_docs(INPUT_HIDDEN_LINE, true);
+        let result = process_docs(INPUT_HIDDEN_LINE, &ReadmeOptions::new());
         assert_eq!(result, EXPECTED_HIDDEN_LINE);
     }
 
+    const EXPECTED_KEPT_HIDDEN_LINE: &[&str] = &[
+        "```rust",
+        "#[visible]",
+        "let visible = \"visible\";",
+        "let hidden = \"hidden\";",
+        "```",
+    ];
+
+    #[test]
+    fn keep_hidden_line_in_rust_code_block() {
+        let result = process_docs(INPUT_HIDDEN_LINE, &ReadmeOptions::new().strip_hidden(false));
+        assert_eq!(result, EXPECTED_KEPT_HIDDEN_LINE);
+    }
+
+    const INPUT_HIDDEN_LINE_RULES: &[&str] = &[
+        "```",
+        "# hidden",
+        "    # indented hidden",
+        "#",
+        "## still hidden?",
+        "#[attr]",
+        "#![inner_attr]",
+        "#hash",
+        "```",
+    ];
+
+    #[test]
+    fn hide_lines_like_rustdoc() {
+        let result = process_docs(INPUT_HIDDEN_LINE_RULES, &ReadmeOptions::new());
+        assert_eq!(
+            result,
+            &[
+                "```rust",
+                "# still hidden?",
+                "#[attr]",
+                "#![inner_attr]",
+                "#hash",
+                "```"
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_hidden_lines_like_rustdoc() {
+        let result = process_docs(
+            INPUT_HIDDEN_LINE_RULES,
+            &ReadmeOptions::new().strip_hidden(false),
+        );
+        assert_eq!(
+            result,
+            &[
+                "```rust",
+                "hidden",
+                "    indented hidden",
+                "",
+                "# still hidden?",
+                "#[attr]",
+                "#![inner_attr]",
+                "#hash",
+                "```"
+            ]
+        );
+    }
+
+    #[test]
+    fn hide_only_hidden_lines_in_rust_code_block() {
+        let input = &[
+            "```",
+            "# hidden",
+            "##not-hidden",
+            "## comment",
+            "#[attribute]",
+            "let heading = \"# not hidden\";",
+            "    \"# not hidden either\",",
+            "```",
+        ];
+        let result = process_docs(input, &ReadmeOptions::new());
+        assert_eq!(
+            result,
+            &[
+                "```rust",
+                "#not-hidden",
+                "# comment",
+                "#[attribute]",
+                "let heading = \"# not hidden\";",
+                "    \"# not hidden either\",",
+                "```"
+            ]
+        );
+    }
+
     const INPUT_NOT_HIDDEN_LINE: &[&str] = &[
         "```",
         "let visible = \"visible\";",
@@ -142,7 +443,7 @@ mod tests {
 
     #[test]
     fn do_not_hide_line_in_code_block() {
-        let result = process_docs(INPUT_NOT_HIDDEN_LINE, true);
+        let result = process_docs(INPUT_NOT_HIDDEN_LINE, &ReadmeOptions::new());
         assert_eq!(result, EXPECTED_NOT_HIDDEN_LINE);
     }
 
@@ -192,10 +493,56 @@ mod tests {
 
     #[test]
     fn transform_rust_code_block() {
-        let result = process_docs(INPUT_RUST_CODE_BLOCK, true);
+        let result = process_docs(INPUT_RUST_CODE_BLOCK, &ReadmeOptions::new());
         assert_eq!(result, EXPECTED_RUST_CODE_BLOCK);
     }
 
+    #[test]
+    fn transform_rust_code_block_with_rs_label() {
+        let result = process_docs(
+            INPUT_RUST_CODE_BLOCK,
+            &ReadmeOptions::new().rust_fence_label("rs"),
+        );
+        let expected: Vec<String> = EXPECTED_RUST_CODE_BLOCK
+            .iter()
+            .map(|line| line.replace("```rust", "```rs"))
+            .collect();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn transform_rust_code_block_with_empty_label() {
+        let result = process_docs(
+            INPUT_RUST_CODE_BLOCK,
+            &ReadmeOptions::new().rust_fence_label(""),
+        );
+        let expected: Vec<String> = EXPECTED_RUST_CODE_BLOCK
+            .iter()
+            .map(|line| line.replace("```rust", "```"))
+            .collect();
+        assert_eq!(result, expected);
+        assert!(result.contains(&"```C".to_owned()));
+    }
+
+    #[test]
+    fn fence_indented_code_block_with_label() {
+        let result = process_docs(
+            &["    let x = 1;"][..],
+            &ReadmeOptions::new()
+                .fence_indented(true)
+                .rust_fence_label("rs"),
+        );
+        assert_eq!(result, &["```rs", "let x = 1;", "```"]);
+
+        let result = process_docs(
+            &["    let x = 1;"][..],
+            &ReadmeOptions::new()
+                .fence_indented(true)
+                .rust_fence_label(""),
+        );
+        assert_eq!(result, &["```", "let x = 1;", "```"]);
+    }
+
     const INPUT_RUST_CODE_BLOCK_RUST_PREFIX: &[&str] = &[
         "```rust",
         "let block = \"simple code block\";",
@@ -220,18 +567,42 @@ mod tests {
 
     #[test]
     fn transform_rust_code_block_with_prefix() {
-        let result = process_docs(INPUT_RUST_CODE_BLOCK_RUST_PREFIX, true);
+        let result = process_docs(INPUT_RUST_CODE_BLOCK_RUST_PREFIX, &ReadmeOptions::new());
         assert_eq!(result, EXPECTED_RUST_CODE_BLOCK);
     }
 
     const INPUT_TEXT_BLOCK: &[&str] = &["```text", "this is text", "```"];
 
-    const EXPECTED_TEXT_BLOCK: &[&str] = &["```", "this is text", "```"];
+    #[test]
+    fn keep_text_block() {
+        let result = process_docs(INPUT_TEXT_BLOCK, &ReadmeOptions::new());
+        assert_eq!(result, INPUT_TEXT_BLOCK);
+    }
+
+    const INPUT_OTHER_LANGUAGES: &[&str] = &[
+        "```console",
+        "# this is a root prompt",
+        "$ cargo readme",
+        "```",
+        "",
+        "~~~json",
+        "{ \"key\": \"value\" }",
+        "~~~",
+        "",
+        "```toml",
+        "# comment",
+        "[package]",
+        "```",
+        "",
+        "````sh",
+        "# comment",
+        "````",
+    ];
 
     #[test]
-    fn transform_text_block() {
-        let result = process_docs(INPUT_TEXT_BLOCK, true);
-        assert_eq!(result, EXPECTED_TEXT_BLOCK);
+    fn keep_other_language_blocks() {
+        let result = process_docs(INPUT_OTHER_LANGUAGES, &ReadmeOptions::new());
+        assert_eq!(result, INPUT_OTHER_LANGUAGES);
     }
 
     const INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS: &[&str] = &[
@@ -246,7 +617,7 @@ mod tests {
 
     #[test]
     fn transform_other_code_block_with_symbols() {
-        let result = process_docs(INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS, true);
+        let result = process_docs(INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS, &ReadmeOptions::new());
         assert_eq!(result, INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS);
     }
 
@@ -266,23 +637,86 @@ mod tests {
 
     #[test]
     fn indent_markdown_headings() {
-        let result = process_docs(INPUT_INDENT_HEADINGS, true);
+        let result = process_docs(INPUT_INDENT_HEADINGS, &ReadmeOptions::new());
         assert_eq!(result, EXPECTED_INDENT_HEADINGS);
     }
 
     #[test]
     fn do_not_indent_markdown_headings() {
-        let result = process_docs(INPUT_INDENT_HEADINGS, false);
+        let result = process_docs(
+            INPUT_INDENT_HEADINGS,
+            &ReadmeOptions::new().heading_indent(0),
+        );
         assert_eq!(result, INPUT_INDENT_HEADINGS);
     }
 
+    const EXPECTED_INDENT_HEADINGS_2_LEVELS: &[&str] = &[
+        "### heading 1",
+        "some text",
+        "#### heading 2",
+        "some other text",
+    ];
+
+    #[test]
+    fn indent_markdown_headings_2_levels() {
+        let result = process_docs(
+            INPUT_INDENT_HEADINGS,
+            &ReadmeOptions::new().heading_indent(2),
+        );
+        assert_eq!(result, EXPECTED_INDENT_HEADINGS_2_LEVELS);
+    }
+
+    const INPUT_DEEP_HEADINGS: &[&str] = &["#### heading 4", "##### heading 5", "###### heading 6"];
+
+    const EXPECTED_DEEP_HEADINGS_2_LEVELS: &[&str] =
+        &["###### heading 4", "###### heading 5", "###### heading 6"];
+
+    #[test]
+    fn indent_deep_markdown_headings_up_to_level_6() {
+        let result = process_docs(INPUT_DEEP_HEADINGS, &ReadmeOptions::new().heading_indent(2));
+        assert_eq!(result, EXPECTED_DEEP_HEADINGS_2_LEVELS);
+    }
+
+    const INPUT_NO_INDENT_HEADINGS: &[&str] = &[
+        "# Usage",
+        "some text",
+        "# Examples",
+        "## Simple",
+        "```",
+        "# examples",
+        "```",
+        "# examples #",
+    ];
+
+    const EXPECTED_NO_INDENT_HEADINGS: &[&str] = &[
+        "## Usage",
+        "some text",
+        "# Examples",
+        "### Simple",
+        "```rust",
+        "```",
+        "# examples #",
+    ];
+
+    #[test]
+    fn indent_markdown_headings_except_examples() {
+        let result = process_docs(
+            INPUT_NO_INDENT_HEADINGS,
+            &ReadmeOptions::new().no_indent_headings(vec!["Examples".to_owned()]),
+        );
+        assert_eq!(result, EXPECTED_NO_INDENT_HEADINGS);
+    }
+
     const INPUT_ALTERNATE_DELIMITER_4_BACKTICKS: &[&str] = &["````", "let i = 1;", "````"];
 
     const EXPECTED_ALTERNATE_DELIMITER_4_BACKTICKS: &[&str] = &["````rust", "let i = 1;", "````"];
 
     #[test]
     fn alternate_delimiter_4_backticks() {
-        let result = process_docs(INPUT_ALTERNATE_DELIMITER_4_BACKTICKS, false);
+        let result = process_docs(
+            INPUT_ALTERNATE_DELIMITER_4_BACKTICKS,
+            &ReadmeOptions::new().heading_indent(0),
+        );
         assert_eq!(result, EXPECTED_ALTERNATE_DELIMITER_4_BACKTICKS);
     }
 
@@ -310,7 +744,10 @@ mod tests {
 
     #[test]
     fn alternate_delimiter_4_backticks_nested() {
-        let result = process_docs(INPUT_ALTERNATE_DELIMITER_4_BACKTICKS_NESTED, false);
+        let result = process_docs(
+            INPUT_ALTERNATE_DELIMITER_4_BACKTICKS_NESTED,
+            &ReadmeOptions::new().heading_indent(0),
+        );
         assert_eq!(result, EXPECTED_ALTERNATE_DELIMITER_4_BACKTICKS_NESTED);
     }
 
@@ -320,7 +757,10 @@ mod tests {
 
     #[test]
     fn alternate_delimiter_3_tildes() {
-        let result = process_docs(INPUT_ALTERNATE_DELIMITER_3_TILDES, false);
+        let result = process_docs(
+            INPUT_ALTERNATE_DELIMITER_3_TILDES,
+            &ReadmeOptions::new().heading_indent(0),
+        );
         assert_eq!(result, EXPECTED_ALTERNATE_DELIMITER_3_TILDES);
     }
 
@@ -330,10 +770,72 @@ mod tests {
 
     #[test]
     fn alternate_delimiter_4_tildes() {
-        let result = process_docs(INPUT_ALTERNATE_DELIMITER_4_TILDES, false);
+        let result = process_docs(
+            INPUT_ALTERNATE_DELIMITER_4_TILDES,
+            &ReadmeOptions::new().heading_indent(0),
+        );
         assert_eq!(result, EXPECTED_ALTERNATE_DELIMITER_4_TILDES);
     }
 
+    const INPUT_ALTERNATE_DELIMITER_TILDES_RUST: &[&str] = &[
+        "~~~rust",
+        "let i = 1;",
+        "# let hidden = 2;",
+        "~~~",
+        "",
+        "~~~no_run",
+        "let i = 1;",
+        "~~~",
+    ];
+
+    const EXPECTED_ALTERNATE_DELIMITER_TILDES_RUST: &[&str] = &[
+        "~~~rust",
+        "let i = 1;",
+        "~~~",
+        "",
+        "~~~rust",
+        "let i = 1;",
+        "~~~",
+    ];
+
+    #[test]
+    fn alternate_delimiter_tildes_rust() {
+        let result = process_docs(
+            INPUT_ALTERNATE_DELIMITER_TILDES_RUST,
+            &ReadmeOptions::new().heading_indent(0),
+        );
+        assert_eq!(result, EXPECTED_ALTERNATE_DELIMITER_TILDES_RUST);
+    }
+
+    const INPUT_ALTERNATE_DELIMITER_NOT_CLOSED_BY_OTHER_STYLE: &[&str] = &[
+        "~~~",
+        "let s = \"\\n```\\n\";",
+        "```",
+        "# let hidden = 1;",
+        "~~~",
+        "# heading",
+    ];
+
+    const EXPECTED_ALTERNATE_DELIMITER_NOT_CLOSED_BY_OTHER_STYLE: &[&str] = &[
+        "~~~rust",
+        "let s = \"\\n```\\n\";",
+        "```",
+        "~~~",
+        "## heading",
+    ];
+
+    #[test]
+    fn alternate_delimiter_not_closed_by_other_style() {
+        let result = process_docs(
+            INPUT_ALTERNATE_DELIMITER_NOT_CLOSED_BY_OTHER_STYLE,
+            &ReadmeOptions::new(),
+        );
+        assert_eq!(
+            result,
+            EXPECTED_ALTERNATE_DELIMITER_NOT_CLOSED_BY_OTHER_STYLE
+        );
+    }
+
     const INPUT_ALTERNATE_DELIMITER_MIXED: &[&str] = &[
         "```",
         "let i = 1;",
@@ -374,7 +876,302 @@ mod tests {
 
     #[test]
     fn alternate_delimiter_mixed() {
-        let result = process_docs(INPUT_ALTERNATE_DELIMITER_MIXED, false);
+        let result = process_docs(
+            INPUT_ALTERNATE_DELIMITER_MIXED,
+            &ReadmeOptions::new().heading_indent(0),
+        );
         assert_eq!(result, EXPECTED_ALTERNATE_DELIMITER_MIXED);
     }
+
+    const INPUT_INDENTED_CODE_BLOCK: &[&str] = &[
+        "# heading",
+        "",
+        "    #[derive(Debug)]",
+        "    # struct Hidden;",
+        "    struct Visible;",
+        "",
+        "    # fn main() {}",
+        "text",
+        "",
+        "- list item",
+        "",
+        "    list continuation",
+        "# heading",
+        "    let after_heading = 1;",
+    ];
+
+    const EXPECTED_INDENTED_CODE_BLOCK: &[&str] = &[
+        "## heading",
+        "",
+        "    #[derive(Debug)]",
+        "    struct Visible;",
+        "",
+        "text",
+        "",
+        "- list item",
+        "",
+        "    list continuation",
+        "## heading",
+        "    let after_heading = 1;",
+    ];
+
+    #[test]
+    fn keep_indented_code_block() {
+        let result = process_docs(INPUT_INDENTED_CODE_BLOCK, &ReadmeOptions::new());
+        assert_eq!(result, EXPECTED_INDENTED_CODE_BLOCK);
+    }
+
+    const EXPECTED_FENCED_INDENTED_CODE_BLOCK: &[&str] = &[
+        "## heading",
+        "",
+        "```rust",
+        "#[derive(Debug)]",
+        "struct Visible;",
+        "```",
+        "",
+        "text",
+        "",
+        "- list item",
+        "",
+        "    list continuation",
+        "## heading",
+        "```rust",
+        "let after_heading = 1;",
+        "```",
+    ];
+
+    #[test]
+    fn fence_indented_code_block() {
+        let result = process_docs(
+            INPUT_INDENTED_CODE_BLOCK,
+            &ReadmeOptions::new().fence_indented(true),
+        );
+        assert_eq!(result, EXPECTED_FENCED_INDENTED_CODE_BLOCK);
+    }
+
+    const INPUT_INDENTED_CODE_BLOCK_TRAILING_BLANK_LINES: &[&str] = &[
+        "text",
+        "",
+        "\tlet a = 1;",
+        "",
+        "    let b = 2;",
+        "",
+        "",
+        "text",
+    ];
+
+    const EXPECTED_INDENTED_CODE_BLOCK_TRAILING_BLANK_LINES: &[&str] = &[
+        "text",
+        "",
+        "```rust",
+        "let a = 1;",
+        "",
+        "let b = 2;",
+        "```",
+        "",
+        "",
+        "text",
+    ];
+
+    #[test]
+    fn fence_indented_code_block_with_trailing_blank_lines() {
+        let result = process_docs(
+            INPUT_INDENTED_CODE_BLOCK_TRAILING_BLANK_LINES,
+            &ReadmeOptions::new().fence_indented(true),
+        );
+        assert_eq!(result, EXPECTED_INDENTED_CODE_BLOCK_TRAILING_BLANK_LINES);
+    }
+
+    const INPUT_RUST_CODE_BLOCK_ATTRIBUTES: &[&str] = &[
+        "```edition2018",
+        "let edition = 2018;",
+        "```",
+        "",
+        "```rust,edition2021",
+        "let edition = 2021;",
+        "```",
+        "",
+        "```rust,ignore,should_panic",
+        "panic!(\"ignored\");",
+        "```",
+        "",
+        "```compile_fail, E0308",
+        "let i: i32 = \"string\";",
+        "```",
+        "",
+        "```ignore-windows",
+        "let os = \"not windows\";",
+        "```",
+        "",
+        "```no_run,python",
+        "# not rust",
+        "```",
+    ];
+
+    const EXPECTED_RUST_CODE_BLOCK_ATTRIBUTES: &[&str] = &[
+        "```rust",
+        "let edition = 2018;",
+        "```",
+        "",
+        "```rust",
+        "let edition = 2021;",
+        "```",
+        "",
+        "```rust",
+        "panic!(\"ignored\");",
+        "```",
+        "",
+        "```rust",
+        "let i: i32 = \"string\";",
+        "```",
+        "",
+        "```rust",
+        "let os = \"not windows\";",
+        "```",
+        "",
+        "```no_run,python",
+        "# not rust",
+        "```",
+    ];
+
+    #[test]
+    fn transform_rust_code_block_with_attributes() {
+        let result = process_docs(INPUT_RUST_CODE_BLOCK_ATTRIBUTES, &ReadmeOptions::new());
+        assert_eq!(result, EXPECTED_RUST_CODE_BLOCK_ATTRIBUTES);
+    }
+
+    #[test]
+    fn transform_rust_code_block_with_any_attribute() {
+        let input = &[
+            "```rust,compile_fail",
+            "let i: i32 = \"string\";",
+            "```",
+            "",
+            "```rust,edition2024",
+            "let edition = 2024;",
+            "```",
+            "",
+            "```rust, unknown_attribute",
+            "let attribute = \"from a newer rustdoc\";",
+            "```",
+            "",
+            "```text,rust",
+            "not rust",
+            "```",
+        ];
+        let result = process_docs(input, &ReadmeOptions::new());
+        assert_eq!(
+            result,
+            &[
+                "```rust",
+                "let i: i32 = \"string\";",
+                "```",
+                "",
+                "```rust",
+                "let edition = 2024;",
+                "```",
+                "",
+                "```rust",
+                "let attribute = \"from a newer rustdoc\";",
+                "```",
+                "",
+                "```text,rust",
+                "not rust",
+                "```",
+            ]
+        );
+    }
+
+    const INPUT_RUST_CODE_BLOCK_ATTRIBUTES_LIST: &[&str] = &[
+        "```should_panic,no_run",
+        "panic!(\"not run\");",
+        "```",
+        "",
+        "``` rust, ignore ",
+        "let ignore = true;",
+        "```",
+    ];
+
+    const EXPECTED_RUST_CODE_BLOCK_ATTRIBUTES_LIST: &[&str] = &[
+        "```rust",
+        "panic!(\"not run\");",
+        "```",
+        "",
+        "```rust",
+        "let ignore = true;",
+        "```",
+    ];
+
+    #[test]
+    fn transform_rust_code_block_with_attribute_list() {
+        let result = process_docs(INPUT_RUST_CODE_BLOCK_ATTRIBUTES_LIST, &ReadmeOptions::new());
+        assert_eq!(result, EXPECTED_RUST_CODE_BLOCK_ATTRIBUTES_LIST);
+    }
+
+    const INPUT_TITLED_INFO_STRINGS: &[&str] = &[
+        "```python title=\"example.py\"",
+        "print(\"```\")",
+        "```",
+        "",
+        "~~~~ sh {.line-numbers}",
+        "~~~",
+        "cargo readme",
+        "~~~~~~",
+        "",
+        "  ```json title=\"data.json\"",
+        "  {}",
+        "  ```",
+    ];
+
+    #[test]
+    fn keep_titled_info_strings() {
+        let result = process_docs(INPUT_TITLED_INFO_STRINGS, &ReadmeOptions::new());
+        assert_eq!(
+            result,
+            &[
+                "```python title=\"example.py\"",
+                "print(\"```\")",
+                "```",
+                "",
+                "~~~~ sh {.line-numbers}",
+                "~~~",
+                "cargo readme",
+                "~~~~~~",
+                "",
+                "  ```json title=\"data.json\"",
+                "  {}",
+                "  ```",
+            ]
+        );
+    }
+
+    const INPUT_FENCE_CLOSING_RULES: &[&str] = &[
+        "````",
+        "```",
+        "# hidden",
+        "````  ",
+        "# heading",
+        "   ```",
+        "let x = 1;",
+        "    ```",
+        "`````",
+    ];
+
+    #[test]
+    fn close_code_blocks_like_commonmark() {
+        let result = process_docs(INPUT_FENCE_CLOSING_RULES, &ReadmeOptions::new());
+        assert_eq!(
+            result,
+            &[
+                "````rust",
+                "```",
+                "````  ",
+                "## heading",
+                "   ```rust",
+                "let x = 1;",
+                "    ```",
+                "`````",
+            ]
+        );
+    }
 }
@@ -0,0 +1,157 @@
+//! Convert the generated markdown into reStructuredText
+//!
+//! This is not a markdown parser, only the constructs found in the output of cargo-readme are
+//! converted: headings, code blocks, badges, links, images and inline code. Lists, emphasis and
+//! strong emphasis are written the same way in both formats.
+
+use regex::{Captures, Regex};
+
+use super::headings::parse_heading;
+
+// Characters underlining the headings of each level
+const UNDERLINES: &[char] = &['=', '-', '~', '^', '"', '\''];
+
+lazy_static! {
+    // Fence opening or closing a code block, like "```rust" or "~~~"
+    static ref RE_FENCE: Regex = Regex::new(r"^\s*(?P<delimiter>`{3,}|~{3,})\s*(?P<info>[^`\s,]*)").unwrap();
+    // Badge on its own line, like "[![alt](image)](target)"
+    static ref RE_BADGE: Regex = Regex::new(r"^\[!\[(?P<alt>[^\]]*)\]\((?P<image>[^)]*)\)\]\((?P<target>[^)]*)\)$").unwrap();
+    // Image on its own line, like "![alt](image)"
+    static ref RE_IMAGE: Regex = Regex::new(r"^!\[(?P<alt>[^\]]*)\]\((?P<image>[^)]*)\)$").unwrap();
+    // Inline code, like "`code`", or inline link, like "[text](target)", matched together so the
+    // links inside code are left alone
+    static ref RE_INLINE: Regex = Regex::new(r"`(?P<code>[^`]+)`|\[(?P<text>[^\[\]]+)\]\((?P<target>[^()\s]+)\)").unwrap();
+}
+
+/// Convert the markdown readme into reStructuredText
+pub fn markdown_to_rst(readme: &str) -> String {
+    let mut result: Vec<String> = Vec::new();
+    // delimiter of the code block being read, if any
+    let mut code_delimiter: Option<String> = None;
+
+    for line in readme.lines() {
+        if let Some(delimiter) = code_delimiter.take() {
+            if line.trim() == delimiter {
+                result.push(String::new());
+            } else {
+                result.push(if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("   {}", line)
+                });
+                code_delimiter = Some(delimiter);
+            }
+            continue;
+        }
+
+        if let Some(cap) = RE_FENCE.captures(line) {
+            let info = &cap["info"];
+            result.push(if info.is_empty() {
+                "::".to_owned()
+            } else {
+                format!(".. code-block:: {}", info)
+            });
+            result.push(String::new());
+            code_delimiter = Some(cap["delimiter"].to_owned());
+            continue;
+        }
+
+        if let Some((level, text)) = parse_heading(line) {
+            let text = convert_inline(text);
+            let underline = UNDERLINES[level - 1]
+                .to_string()
+                .repeat(text.chars().count());
+            result.push(text);
+            result.push(underline);
+            continue;
+        }
+
+        if let Some(cap) = RE_BADGE.captures(line) {
+            result.extend(image(&cap["image"], &cap["alt"], Some(&cap["target"])));
+            continue;
+        }
+
+        if let Some(cap) = RE_IMAGE.captures(line) {
+            result.extend(image(&cap["image"], &cap["alt"], None));
+            continue;
+        }
+
+        result.push(convert_inline(line));
+    }
+
+    result.join("\n")
+}
+
+/// Image directive, with a link if `target` is given
+fn image(image: &str, alt: &str, target: Option<&str>) -> Vec<String> {
+    let mut lines = vec![format!(".. image:: {}", image)];
+    if !alt.is_empty() {
+        lines.push(format!("   :alt: {}", alt));
+    }
+    if let Some(target) = target {
+        lines.push(format!("   :target: {}", target));
+    }
+    lines
+}
+
+/// Convert inline code and links
+///
+/// Links become anonymous hyperlinks, like "`text <target>`__", so two links with the same text
+/// and different targets do not conflict.
+fn convert_inline(line: &str) -> String {
+    RE_INLINE
+        .replace_all(line, |cap: &Captures| match cap.name("code") {
+            Some(code) => format!("``{}``", code.as_str()),
+            None => format!("`{} <{}>`__", &cap["text"], &cap["target"]),
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::markdown_to_rst;
+
+    #[test]
+    fn convert_headings() {
+        let markdown = "# my-crate\n\nText\n\n## Usage\n\n### Details ###";
+        let expected = "my-crate\n========\n\nText\n\nUsage\n-----\n\nDetails\n~~~~~~~";
+        assert_eq!(markdown_to_rst(markdown), expected);
+    }
+
+    #[test]
+    fn convert_code_blocks() {
+        let markdown =
+            "Example:\n\n```rust\nfn main() {\n\n    # not a heading\n}\n```\n\n~~~\nplain\n~~~";
+        let expected = "Example:\n\n\
+                        .. code-block:: rust\n\n\
+                        \x20  fn main() {\n\n\
+                        \x20      # not a heading\n\
+                        \x20  }\n\n\n\
+                        ::\n\n\
+                        \x20  plain\n";
+        assert_eq!(markdown_to_rst(markdown), expected);
+    }
+
+    #[test]
+    fn convert_badges_and_images() {
+        let markdown = "[![Build Status](https://ci/badge.svg)](https://ci)\n![](logo.png)";
+        let expected =
+            ".. image:: https://ci/badge.svg\n   :alt: Build Status\n   :target: https://ci\n\
+                        .. image:: logo.png";
+        assert_eq!(markdown_to_rst(markdown), expected);
+    }
+
+    #[test]
+    fn convert_inline_code_and_links() {
+        let markdown = "Use `cargo readme` and see [the docs](https://docs.rs).";
+        let expected = "Use ``cargo readme`` and see `the docs <https://docs.rs>`__.";
+        assert_eq!(markdown_to_rst(markdown), expected);
+    }
+
+    #[test]
+    fn keep_links_in_inline_code() {
+        let markdown = "Write `[docs](https://docs.rs)` for [docs](https://docs.rs).";
+        let expected = "Write ``[docs](https://docs.rs)`` for `docs <https://docs.rs>`__.";
+        assert_eq!(markdown_to_rst(markdown), expected);
+    }
+}
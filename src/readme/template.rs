@@ -1,37 +1,143 @@
+use regex::{Captures, Regex};
+
 use config::Manifest;
+use log;
+
+use super::headings;
+use super::ReadmeOptions;
+
+// Escaped tag, like `\{{readme}}`, which is output without the backslash
+const ESCAPED_TAG_START: &str = "\\{{";
+// Escaped tags are replaced by this until the substitutions are done
+const ESCAPE_PLACEHOLDER: &str = "\u{0}ESCAPED_TAG_START\u{0}";
+
+/// The tags substituted in templates, with a description of their value
+pub const TEMPLATE_TAGS: &[(&str, &str)] = &[
+    (
+        "{{readme}}",
+        "documentation extracted from the doc comments",
+    ),
+    ("{{crate}}", "crate name"),
+    (
+        "{{badges}}",
+        "badges from the `[badges]` section of Cargo.toml",
+    ),
+    ("{{license}}", "license from Cargo.toml"),
+    (
+        "{{toc}}",
+        "table of contents, with links to the headings of the readme",
+    ),
+    (
+        "{{date}}",
+        "current date, like 2024-05-01, or CARGO_README_DATE if set",
+    ),
+    ("{{version}}", "version from Cargo.toml"),
+    ("{{description}}", "description from Cargo.toml"),
+    ("{{repository}}", "repository from Cargo.toml"),
+    ("{{homepage}}", "homepage from Cargo.toml"),
+    ("{{documentation}}", "documentation from Cargo.toml"),
+    (
+        "{{authors}}",
+        "authors from Cargo.toml, separated by commas",
+    ),
+    (
+        "{{#if name}}...{{/if}}",
+        "content kept only if `name` is set, one of license, description or version",
+    ),
+];
+
+lazy_static! {
+    // Template tag, like "{{readme}}"
+    static ref RE_TAG: Regex = Regex::new(r"\{\{[a-z_]+\}\}").unwrap();
+    // Conditional block, like "{{#if license}}License: {{license}}{{/if}}"
+    static ref RE_CONDITION: Regex =
+        Regex::new(r"(?s)\{\{#if (?P<name>[a-z_]+)\}\}(?P<body>.*?)\{\{/if\}\}").unwrap();
+    // Token of a license expression: a parenthesis, the "/" of legacy expressions like
+    // "MIT/Apache-2.0", or an operator or license id
+    static ref RE_LICENSE_TOKEN: Regex = Regex::new(r"[()/]|[^\s()/]+").unwrap();
+    // SPDX license or exception id, like "Apache-2.0" or "GPL-2.0+"
+    static ref RE_SPDX_ID: Regex = Regex::new(r"^[A-Za-z0-9][A-Za-z0-9.-]*\+?$").unwrap();
+}
+
+/// Values from `Cargo.toml` written in the readme
+#[derive(Clone, Copy, Default)]
+struct CrateInfo<'a> {
+    /// Crate name, or its title case form
+    title: &'a str,
+    badges: &'a [&'a str],
+    license: Option<&'a str>,
+    license_file: Option<&'a str>,
+    /// Tags from the `[package]` section, like `version`, with their value if it is set
+    metadata: &'a [(&'a str, Option<&'a str>)],
+}
 
 /// Renders the template
 ///
 /// This is not a real template engine, it just processes a few substitutions.
+///
+/// Without template, `license_body` is the text of the license files, written in the license
+/// section instead of the license name.
 pub fn render(
     template: Option<String>,
     readme: String,
     cargo: &Manifest,
-    add_title: bool,
-    add_badges: bool,
-    add_license: bool,
+    options: &ReadmeOptions,
+    license_body: Option<&str>,
 ) -> Result<String, String> {
-    let title: &str = &cargo.name;
+    let title = if options.title_case {
+        title_case_name(&cargo.name)
+    } else {
+        cargo.name.clone()
+    };
+    let title: &str = &title;
 
     let badges: Vec<&str> = cargo.badges.iter().map(AsRef::as_ref).collect();
     let badges: &[&str] = badges.as_ref();
 
     let license: Option<&str> = cargo.license.as_ref().map(AsRef::as_ref);
 
-    let version: &str = cargo.version.as_ref();
+    let license_file: Option<&str> = cargo.license_file.as_ref().map(AsRef::as_ref);
+
+    let authors = cargo.authors.join(", ");
+
+    // metadata tags from the `[package]` section of `Cargo.toml`
+    let metadata: &[(&str, Option<&str>)] = &[
+        ("version", cargo.version.as_ref().map(AsRef::as_ref)),
+        ("description", cargo.description.as_ref().map(AsRef::as_ref)),
+        ("repository", cargo.repository.as_ref().map(AsRef::as_ref)),
+        ("homepage", cargo.homepage.as_ref().map(AsRef::as_ref)),
+        (
+            "documentation",
+            cargo.documentation.as_ref().map(AsRef::as_ref),
+        ),
+        (
+            "authors",
+            if authors.is_empty() {
+                None
+            } else {
+                Some(&authors)
+            },
+        ),
+    ];
+
+    let info = CrateInfo {
+        title,
+        badges,
+        license,
+        license_file,
+        metadata,
+    };
 
     if let Some(template) = template {
-        process_template(template, readme, title, badges, license, version)
+        process_template(template, readme, &info, options)
     } else {
-        process_string(
-            readme,
-            title,
-            badges,
-            license,
-            add_title,
-            add_badges,
-            add_license,
-        )
+        process_string(readme, &info, options, license_body).map(|readme| {
+            if options.append_metadata {
+                append_keywords_and_categories(readme, &cargo.keywords, &cargo.categories)
+            } else {
+                readme
+            }
+        })
     }
 }
 
@@ -42,70 +148,256 @@ pub fn render(
 /// - `{{crate}}` crate name defined in `Cargo.toml`
 /// - `{{badges}}` badges defined in `Cargo.toml`
 /// - `{{license}}` license defined in `Cargo.toml`
-/// - `{{version}}` version defined in `Cargo.toml`
+/// - `{{toc}}` table of contents, with links to the headings of the readme
+/// - `{{date}}` date the readme is generated, kept as it is if the `date` of the options is `None`
+/// - `{{version}}`, `{{description}}`, `{{repository}}`, `{{homepage}}`, `{{documentation}}` and
+///   `{{authors}}`, from the `metadata` defined in `Cargo.toml`
+///
+/// Unknown tags are reported with a warning, or an error if `strict` is set in the options. The whole template
+/// is checked, so a tag in a code block of the template is also reported.
+///
+/// A tag can be escaped with a backslash, so `\{{readme}}` is output as a literal `{{readme}}`.
+///
+/// A `{{#if license}}...{{/if}}` block is kept when the license is defined and removed with its
+/// content otherwise. Only `license`, `description` and `version` can be tested, and the blocks
+/// cannot be nested.
+///
+/// The tags are replaced in a single pass, so the values substituted are never scanned for tags:
+/// docs mentioning `{{crate}}` or `{{license}}` keep them as they are.
 fn process_template(
     mut template: String,
     readme: String,
-    title: &str,
-    badges: &[&str],
-    license: Option<&str>,
-    version: &str,
+    info: &CrateInfo,
+    options: &ReadmeOptions,
 ) -> Result<String, String> {
-    template = template.trim_end_matches("\n").to_owned();
+    let linked_license = info
+        .license
+        .filter(|_| options.license_link)
+        .map(link_licenses);
+    let info = CrateInfo {
+        license: linked_license.as_ref().map(AsRef::as_ref).or(info.license),
+        ..*info
+    };
+    let date = options.date.as_ref().map(AsRef::as_ref);
+
+    template = template
+        .trim_end_matches("\n")
+        .replace(ESCAPED_TAG_START, ESCAPE_PLACEHOLDER);
+
+    template = process_conditions(&template, &info)?;
 
     if !template.contains("{{readme}}") {
         return Err("Missing `{{readme}}` in template".to_owned());
     }
 
-    if template.contains("{{crate}}") {
-        template = template.replace("{{crate}}", &title);
+    let unknown_tags = find_unknown_tags(&template, info.metadata);
+    if !unknown_tags.is_empty() {
+        let message = format!(
+            "Unknown tags found in template: {}",
+            unknown_tags.join(", ")
+        );
+        if options.strict {
+            return Err(message);
+        }
+        log::warn(&message);
     }
 
-    if template.contains("{{badges}}") {
-        if badges.is_empty() {
-            return Err("`{{badges}}` was found in template but no badges were provided".to_owned());
+    // the template is scanned in a single pass, so the values substituted, like the readme, are
+    // output as they are, even if they contain tags themselves
+    let mut result = String::with_capacity(template.len() + readme.len());
+    let mut last = 0;
+    for tag in RE_TAG.find_iter(&template) {
+        let name = &tag.as_str()[2..tag.as_str().len() - 2];
+        // unknown tags are kept as they are
+        if let Some(value) = tag_value(name, &readme, &info, date)? {
+            result.push_str(&template[last..tag.start()].replace(ESCAPE_PLACEHOLDER, "{{"));
+            result.push_str(&value);
+            last = tag.end();
         }
-        let badges = badges.join("\n");
-        template = template.replace("{{badges}}", &badges);
     }
+    result.push_str(&template[last..].replace(ESCAPE_PLACEHOLDER, "{{"));
 
-    if template.contains("{{license}}") {
-        if let Some(license) = license {
-            template = template.replace("{{license}}", &license);
-        } else {
-            return Err(
-                "`{{license}}` was found in template but no license was provided".to_owned(),
-            );
+    Ok(result)
+}
+
+/// Keep or remove the `{{#if name}}...{{/if}}` blocks of the template
+///
+/// When a tag of the block is alone on its line, the line break following it is removed too, so
+/// the block does not leave an empty line behind.
+fn process_conditions(template: &str, info: &CrateInfo) -> Result<String, String> {
+    let mut result = String::with_capacity(template.len());
+    let mut last = 0;
+    for cap in RE_CONDITION.captures_iter(template) {
+        let block = cap.get(0).unwrap();
+        let name = &cap["name"];
+        let mut body = &cap["body"];
+
+        let present = match name {
+            "license" => info.license.is_some(),
+            "description" | "version" => info
+                .metadata
+                .iter()
+                .any(|&(tag_name, value)| tag_name == name && value.is_some()),
+            _ => {
+                return Err(format!(
+                    "Unsupported condition `{{{{#if {}}}}}` in template, expected `license`, \
+                     `description` or `version`",
+                    name
+                ))
+            }
+        };
+        if body.contains("{{#if ") {
+            return Err("Nested `{{#if}}` blocks are not supported in template".to_owned());
         }
+
+        let starts_line = block.start() == 0 || template[..block.start()].ends_with('\n');
+        if starts_line && body.starts_with('\n') {
+            body = &body[1..];
+        }
+        let mut end = block.end();
+        if body.ends_with('\n') && template[end..].starts_with('\n') {
+            end += 1;
+        }
+
+        result.push_str(&template[last..block.start()]);
+        if present {
+            result.push_str(body);
+        }
+        last = end;
     }
+    result.push_str(&template[last..]);
 
-    template = template.replace("{{version}}", version);
+    if result.contains("{{#if ") {
+        return Err("Missing `{{/if}}` after `{{#if}}` in template".to_owned());
+    }
+    if result.contains("{{/if}}") {
+        return Err("Found `{{/if}}` without `{{#if}}` in template".to_owned());
+    }
 
-    let result = template.replace("{{readme}}", &readme);
     Ok(result)
 }
 
+/// Get the value of the template tag `name`, or `None` if the tag is unknown
+fn tag_value(
+    name: &str,
+    readme: &str,
+    info: &CrateInfo,
+    date: Option<&str>,
+) -> Result<Option<String>, String> {
+    let value = match name {
+        "readme" => readme.to_owned(),
+        "date" => match date {
+            Some(date) => date.to_owned(),
+            None => return Ok(None),
+        },
+        "crate" => info.title.to_owned(),
+        "toc" => headings::table_of_contents(readme),
+        "badges" => {
+            if info.badges.is_empty() {
+                return Err(
+                    "`{{badges}}` was found in template but no badges were provided".to_owned(),
+                );
+            }
+            info.badges.join("\n")
+        }
+        "license" => match info.license {
+            Some(license) => license.to_owned(),
+            None => {
+                return Err(
+                    "`{{license}}` was found in template but no license was provided".to_owned(),
+                )
+            }
+        },
+        _ => match info
+            .metadata
+            .iter()
+            .find(|&&(tag_name, _)| tag_name == name)
+        {
+            Some(&(_, Some(value))) => value.to_owned(),
+            Some(&(_, None)) => {
+                let verb = if name.ends_with('s') { "were" } else { "was" };
+                return Err(format!(
+                    "`{{{{{}}}}}` was found in template but no {} {} provided",
+                    name, name, verb
+                ));
+            }
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some(value))
+}
+
+/// Does the template output some crate information, with a tag or a condition
+///
+/// Only `{{readme}}`, `{{toc}}` and `{{date}}` do not depend on `Cargo.toml`.
+pub fn uses_crate_info(template: &str) -> bool {
+    RE_CONDITION.is_match(template)
+        || RE_TAG.find_iter(template).any(|tag| {
+            let name = &tag.as_str()[2..tag.as_str().len() - 2];
+            !["readme", "toc", "date"].contains(&name)
+        })
+}
+
+/// Find the tags in the template that are not substituted, like a misspelled `{{licence}}`
+fn find_unknown_tags(template: &str, metadata: &[(&str, Option<&str>)]) -> Vec<String> {
+    let mut unknown_tags: Vec<String> = Vec::new();
+
+    for tag in RE_TAG.find_iter(template) {
+        let name = &tag.as_str()[2..tag.as_str().len() - 2];
+        let known = ["readme", "crate", "badges", "license", "toc", "date"].contains(&name)
+            || metadata.iter().any(|&(tag_name, _)| tag_name == name);
+        let tag = format!("`{}`", tag.as_str());
+        if !known && !unknown_tags.contains(&tag) {
+            unknown_tags.push(tag);
+        }
+    }
+
+    unknown_tags
+}
+
 /// Process output without template
+///
+/// `license_body` is the text of the license files, written in the license section instead of the
+/// license name.
 fn process_string(
     mut readme: String,
-    title: &str,
-    badges: &[&str],
-    license: Option<&str>,
-    add_title: bool,
-    add_badges: bool,
-    add_license: bool,
+    info: &CrateInfo,
+    options: &ReadmeOptions,
+    license_body: Option<&str>,
 ) -> Result<String, String> {
-    if add_title {
-        readme = prepend_title(readme, title);
+    if options.add_title {
+        readme = prepend_title(readme, info.title, &options.title_format);
     }
 
-    if add_badges {
-        readme = prepend_badges(readme, badges);
+    if options.add_badges {
+        readme = prepend_badges(readme, info.badges);
     }
 
-    if add_license {
-        if let Some(license) = license {
-            readme = append_license(readme, license);
+    // the license section is at the same level as the top headings from the docs
+    let license_heading = if options.license_heading || license_body.is_some() {
+        Some(options.heading_indent + 1)
+    } else {
+        None
+    };
+    let prefix = &options.license_prefix;
+
+    if options.add_license && headings::has_license_section(&readme) {
+        log::warn("The docs already have a license section, the license is not appended");
+    } else if options.add_license {
+        if let Some(body) = license_body {
+            readme = append_license_body(readme, body, license_heading.unwrap_or(1));
+        } else if let Some(license) = info.license {
+            readme = append_license(
+                readme,
+                license,
+                prefix,
+                options.license_prose,
+                options.license_link,
+                license_heading,
+            );
+        } else if let Some(license_file) = info.license_file {
+            readme = append_license_file(readme, license_file, prefix, license_heading);
         }
     }
 
@@ -126,19 +418,50 @@ fn prepend_badges(readme: String, badges: &[&str]) -> String {
     }
 }
 
+/// Write a crate name as words in title case, like "My Cool Crate" for "my-cool_crate"
+fn title_case_name(name: &str) -> String {
+    name.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 /// Prepend title (crate name) to output string
-fn prepend_title(readme: String, crate_name: &str) -> String {
-    let title = format!("# {}", crate_name);
+///
+/// The title is `format` with `{name}` replaced by the crate name, including the new lines
+/// separating it from the readme, like the default "# {name}\n\n".
+fn prepend_title(readme: String, crate_name: &str, format: &str) -> String {
+    let title = format.replace("{name}", crate_name);
     if !readme.trim().is_empty() {
-        format!("{}\n\n{}", title, readme)
+        format!("{}{}", title, readme)
     } else {
-        title
+        title.trim_end().to_owned()
     }
 }
 
 /// Append license to output string
-fn append_license(readme: String, license: &str) -> String {
-    let license = format!("License: {}", license);
+///
+/// With a `heading` level, the license is written in its own section instead of a single line
+/// starting with `prefix`. With `link`, the license ids are links, see `link_licenses`.
+fn append_license(
+    readme: String,
+    license: &str,
+    prefix: &str,
+    prose: bool,
+    link: bool,
+    heading: Option<usize>,
+) -> String {
+    let license = match heading {
+        Some(level) => format_license_section(license, prose, link, level),
+        None => format_license(license, prefix, prose, link),
+    };
     if !readme.trim().is_empty() {
         format!("{}\n\n{}", readme, license)
     } else {
@@ -146,20 +469,201 @@ fn append_license(readme: String, license: &str) -> String {
     }
 }
 
+/// Append the text of the license files in a license section
+fn append_license_body(readme: String, body: &str, level: usize) -> String {
+    let license = license_section(body.trim(), level);
+    if !readme.trim().is_empty() {
+        format!("{}\n\n{}", readme, license)
+    } else {
+        license
+    }
+}
+
+/// Append a reference to the license file to output string
+///
+/// Used when `Cargo.toml` has `license-file` instead of `license`.
+fn append_license_file(
+    readme: String,
+    license_file: &str,
+    prefix: &str,
+    heading: Option<usize>,
+) -> String {
+    let license = match heading {
+        Some(level) => license_section(&format!("See {}", license_file), level),
+        None => format!("{}see {}", prefix, license_file),
+    };
+    if !readme.trim().is_empty() {
+        format!("{}\n\n{}", readme, license)
+    } else {
+        license
+    }
+}
+
+/// Append the crate keywords and categories, each on its own line
+///
+/// Nothing is appended for an empty list.
+fn append_keywords_and_categories(
+    mut readme: String,
+    keywords: &[String],
+    categories: &[String],
+) -> String {
+    for &(label, values) in &[("Keywords", keywords), ("Categories", categories)] {
+        if values.is_empty() {
+            continue;
+        }
+        let line = format!("{}: {}", label, values.join(", "));
+        readme = if !readme.trim().is_empty() {
+            format!("{}\n\n{}", readme, line)
+        } else {
+            line
+        };
+    }
+
+    readme
+}
+
+/// Format the license line, starting with `prefix`
+///
+/// When `prose` is set, SPDX expressions made only of `OR` or only of `AND` are written as a
+/// sentence, like "Licensed under either of MIT or Apache-2.0", which replaces the prefix. Any
+/// other expression, including the ones with parentheses, is written as is.
+fn format_license(license: &str, prefix: &str, prose: bool, link: bool) -> String {
+    let license = license.trim();
+
+    if prose {
+        if let Some(sentence) = license_sentence(license, link) {
+            return sentence;
+        }
+    }
+
+    if link {
+        format!("{}{}", prefix, link_licenses(license))
+    } else {
+        format!("{}{}", prefix, license)
+    }
+}
+
+/// Format the content of the license section, see `format_license`
+fn format_license_section(license: &str, prose: bool, link: bool, level: usize) -> String {
+    let license = license.trim();
+
+    let text = if prose {
+        license_sentence(license, link)
+    } else {
+        None
+    };
+    let text = text.unwrap_or_else(|| {
+        if link {
+            link_licenses(license)
+        } else {
+            license.to_owned()
+        }
+    });
+
+    license_section(&text, level)
+}
+
+fn license_section(text: &str, level: usize) -> String {
+    format!("{} License\n\n{}", "#".repeat(level), text)
+}
+
+/// Write the ids of a SPDX expression as links to their page on spdx.org, like
+/// "[MIT](https://spdx.org/licenses/MIT.html) OR [Apache-2.0](https://spdx.org/licenses/Apache-2.0.html)"
+///
+/// A license which is not a SPDX expression, like "see LICENSE", is returned as is. Custom ids,
+/// like "LicenseRef-Proprietary", have no page and are not linked.
+fn link_licenses(license: &str) -> String {
+    // an expression alternates ids and operators, starting and ending with an id
+    let mut expect_id = true;
+    for token in RE_LICENSE_TOKEN.find_iter(license) {
+        match token.as_str() {
+            "(" | ")" => {}
+            "OR" | "AND" | "WITH" | "/" if !expect_id => expect_id = true,
+            id if expect_id && RE_SPDX_ID.is_match(id) => expect_id = false,
+            _ => return license.to_owned(),
+        }
+    }
+    if expect_id {
+        return license.to_owned();
+    }
+
+    RE_LICENSE_TOKEN
+        .replace_all(license, |cap: &Captures| match &cap[0] {
+            token @ "("
+            | token @ ")"
+            | token @ "/"
+            | token @ "OR"
+            | token @ "AND"
+            | token @ "WITH" => token.to_owned(),
+            id if id.starts_with("LicenseRef-") => id.to_owned(),
+            id => format!("[{}](https://spdx.org/licenses/{}.html)", id, id),
+        })
+        .into_owned()
+}
+
+/// Write a SPDX expression as a sentence, if it is made only of `OR` or only of `AND`
+///
+/// With `link`, the license ids are links, see `link_licenses`.
+fn license_sentence(license: &str, link: bool) -> Option<String> {
+    if license.contains('(') {
+        return None;
+    }
+
+    for &(operator, quantifier, conjunction) in
+        &[(" OR ", "either of", "or"), (" AND ", "all of", "and")]
+    {
+        if !license.contains(operator) {
+            continue;
+        }
+
+        let licenses: Vec<&str> = license.split(operator).map(str::trim).collect();
+        if licenses.iter().any(|l| l.contains(' ')) {
+            return None;
+        }
+        let licenses: Vec<String> = licenses
+            .into_iter()
+            .map(|l| if link { link_licenses(l) } else { l.to_owned() })
+            .collect();
+
+        let (last, rest) = licenses.split_last().unwrap();
+        return Some(format!(
+            "Licensed under {} {} {} {}",
+            quantifier,
+            rest.join(", "),
+            conjunction,
+            last
+        ));
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{CrateInfo, ReadmeOptions};
+
     const TEMPLATE_MINIMAL: &str = "{{readme}}";
     const TEMPLATE_WITH_TITLE: &str = "# {{crate}}\n\n{{readme}}";
     const TEMPLATE_WITH_BADGES: &str = "{{badges}}\n\n{{readme}}";
     const TEMPLATE_WITH_LICENSE: &str = "{{readme}}\n\n{{license}}";
     const TEMPLATE_WITH_VERSION: &str = "{{readme}}\n\n{{version}}";
+    const TEMPLATE_WITH_DESCRIPTION: &str = "{{description}}\n\n{{readme}}";
+    const TEMPLATE_WITH_METADATA: &str =
+        "{{readme}}\n\n{{repository}}\n{{homepage}}\n{{documentation}}\n{{authors}}";
     const TEMPLATE_FULL: &str =
         "{{badges}}\n\n# {{crate}}\n\n{{readme}}\n\n{{license}}\n\n{{version}}";
+    const TEMPLATE_WITH_LICENSE_CONDITION: &str =
+        "{{readme}}\n\n{{#if license}}\n## License\n\n{{license}}\n\n{{/if}}\nThe end";
 
     // process template
     #[test]
     fn template_without_readme_should_fail() {
-        let result = super::process_template(String::new(), String::new(), "", &[], None, "");
+        let result = super::process_template(
+            String::new(),
+            String::new(),
+            &CrateInfo::default(),
+            &ReadmeOptions::new(),
+        );
         assert!(result.is_err());
         assert_eq!("Missing `{{readme}}` in template", result.unwrap_err());
     }
@@ -169,10 +673,8 @@ mod tests {
         let result = super::process_template(
             TEMPLATE_WITH_BADGES.to_owned(),
             String::new(),
-            "",
-            &[],
-            None,
-            "",
+            &CrateInfo::default(),
+            &ReadmeOptions::new(),
         );
         assert!(result.is_err());
         assert_eq!(
@@ -186,10 +688,8 @@ mod tests {
         let result = super::process_template(
             TEMPLATE_WITH_LICENSE.to_owned(),
             String::new(),
-            "",
-            &[],
-            None,
-            "",
+            &CrateInfo::default(),
+            &ReadmeOptions::new(),
         );
         assert!(result.is_err());
         assert_eq!(
@@ -198,29 +698,214 @@ mod tests {
         );
     }
 
+    #[test]
+    fn template_with_version_tag_but_missing_version_should_fail() {
+        let result = super::process_template(
+            TEMPLATE_WITH_VERSION.to_owned(),
+            String::new(),
+            &CrateInfo {
+                metadata: &[("version", None)],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            "`{{version}}` was found in template but no version was provided",
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn template_with_description_tag_but_missing_description_should_fail() {
+        let result = super::process_template(
+            TEMPLATE_WITH_DESCRIPTION.to_owned(),
+            String::new(),
+            &CrateInfo {
+                metadata: &[("description", None)],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            "`{{description}}` was found in template but no description was provided",
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn template_with_metadata_tags_but_missing_metadata_should_fail() {
+        for &(name, verb) in &[
+            ("repository", "was"),
+            ("homepage", "was"),
+            ("documentation", "was"),
+            ("authors", "were"),
+        ] {
+            let result = super::process_template(
+                format!("{{{{readme}}}}\n\n{{{{{}}}}}", name),
+                String::new(),
+                &CrateInfo {
+                    metadata: &[(name, None)],
+                    ..Default::default()
+                },
+                &ReadmeOptions::new(),
+            );
+            assert!(result.is_err());
+            assert_eq!(
+                format!(
+                    "`{{{{{0}}}}}` was found in template but no {0} {1} provided",
+                    name, verb
+                ),
+                result.unwrap_err()
+            );
+        }
+    }
+
+    #[test]
+    fn template_with_unknown_tag() {
+        let result = super::process_template(
+            "{{readme}}\n\n{{licence}}".to_owned(),
+            "readme".to_owned(),
+            &CrateInfo {
+                license: Some("license"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+        );
+        assert!(result.is_ok());
+        assert_eq!("readme\n\n{{licence}}", result.unwrap());
+    }
+
+    #[test]
+    fn template_with_unknown_tag_strict_should_fail() {
+        let result = super::process_template(
+            "{{readme}}\n\n{{licence}} {{crate}} {{licence}} {{verison}}".to_owned(),
+            "readme".to_owned(),
+            &CrateInfo {
+                license: Some("license"),
+                metadata: &[("version", Some("1.0.0"))],
+                ..Default::default()
+            },
+            &ReadmeOptions::new().strict(true),
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            "Unknown tags found in template: `{{licence}}`, `{{verison}}`",
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn template_with_escaped_tags() {
+        let result = super::process_template(
+            "Use \\{{readme}} and \\{{crate}} in `README.tpl`\n\n{{readme}}".to_owned(),
+            "readme".to_owned(),
+            &CrateInfo {
+                title: "title",
+                ..Default::default()
+            },
+            &ReadmeOptions::new().strict(true),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            "Use {{readme}} and {{crate}} in `README.tpl`\n\nreadme",
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn template_with_only_escaped_readme_should_fail() {
+        let result = super::process_template(
+            "\\{{readme}}".to_owned(),
+            "readme".to_owned(),
+            &CrateInfo::default(),
+            &ReadmeOptions::new(),
+        );
+        assert!(result.is_err());
+        assert_eq!("Missing `{{readme}}` in template", result.unwrap_err());
+    }
+
+    #[test]
+    fn template_does_not_substitute_tags_in_readme() {
+        let readme = "{{readme}} {{crate}} {{badges}} {{license}} {{version}} {{licence}}";
+        let result = super::process_template(
+            TEMPLATE_FULL.to_owned(),
+            readme.to_owned(),
+            &CrateInfo {
+                title: "title",
+                badges: &["badge"],
+                license: Some("license"),
+                metadata: &[("version", Some("1.0.0"))],
+                ..Default::default()
+            },
+            &ReadmeOptions::new().strict(true),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            format!("badge\n\n# title\n\n{}\n\nlicense\n\n1.0.0", readme),
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn template_does_not_substitute_tags_in_values() {
+        let result = super::process_template(
+            "{{crate}}\n\n{{description}}\n\n{{readme}}".to_owned(),
+            "readme".to_owned(),
+            &CrateInfo {
+                title: "{{license}}",
+                license: Some("license"),
+                metadata: &[("description", Some("{{crate}} {{readme}}"))],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            "{{license}}\n\n{{crate}} {{readme}}\n\nreadme",
+            result.unwrap()
+        );
+    }
+
     #[test]
     fn template_minimal() {
         let result = super::process_template(
             TEMPLATE_MINIMAL.to_owned(),
             "readme".to_owned(),
-            "",
-            &[],
-            None,
-            "",
+            &CrateInfo::default(),
+            &ReadmeOptions::new(),
         );
         assert!(result.is_ok());
         assert_eq!("readme", result.unwrap());
     }
 
+    #[test]
+    fn template_with_toc() {
+        let result = super::process_template(
+            "{{toc}}\n\n{{readme}}".to_owned(),
+            "## First\n\n```sh\n# comment\n```\n\n### Second".to_owned(),
+            &CrateInfo::default(),
+            &ReadmeOptions::new(),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            "- [First](#first)\n  - [Second](#second)\n\n\
+             ## First\n\n```sh\n# comment\n```\n\n### Second",
+            result.unwrap()
+        );
+    }
+
     #[test]
     fn template_with_title() {
         let result = super::process_template(
             TEMPLATE_WITH_TITLE.to_owned(),
             "readme".to_owned(),
-            "title",
-            &[],
-            None,
-            "",
+            &CrateInfo {
+                title: "title",
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
         );
         assert!(result.is_ok());
         assert_eq!("# title\n\nreadme", result.unwrap());
@@ -231,10 +916,11 @@ mod tests {
         let result = super::process_template(
             TEMPLATE_WITH_BADGES.to_owned(),
             "readme".to_owned(),
-            "",
-            &["badge1", "badge2"],
-            None,
-            "",
+            &CrateInfo {
+                badges: &["badge1", "badge2"],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
         );
         assert!(result.is_ok());
         assert_eq!("badge1\nbadge2\n\nreadme", result.unwrap());
@@ -245,10 +931,11 @@ mod tests {
         let result = super::process_template(
             TEMPLATE_WITH_LICENSE.to_owned(),
             "readme".to_owned(),
-            "",
-            &[],
-            Some("license"),
-            "",
+            &CrateInfo {
+                license: Some("license"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
         );
         assert!(result.is_ok());
         assert_eq!("readme\n\nlicense", result.unwrap());
@@ -259,24 +946,120 @@ mod tests {
         let result = super::process_template(
             TEMPLATE_WITH_VERSION.to_owned(),
             "readme".to_owned(),
-            "",
-            &[],
-            None,
-            "3.0.1",
+            &CrateInfo {
+                metadata: &[("version", Some("3.0.1"))],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
         );
         assert!(result.is_ok());
         assert_eq!("readme\n\n3.0.1", result.unwrap());
     }
 
+    #[test]
+    fn template_with_date() {
+        let result = super::process_template(
+            "{{readme}}\n\nGenerated on {{date}}".to_owned(),
+            "readme".to_owned(),
+            &CrateInfo::default(),
+            &ReadmeOptions::new()
+                .date(Some("2024-05-01".to_owned()))
+                .strict(true),
+        );
+        assert_eq!(Ok("readme\n\nGenerated on 2024-05-01".to_owned()), result);
+    }
+
+    #[test]
+    fn template_with_date_tag_but_no_date() {
+        let result = super::process_template(
+            "{{readme}}\n\nGenerated on {{date}}".to_owned(),
+            "readme".to_owned(),
+            &CrateInfo::default(),
+            &ReadmeOptions::new().strict(true),
+        );
+        assert_eq!(Ok("readme\n\nGenerated on {{date}}".to_owned()), result);
+    }
+
+    #[test]
+    fn template_tags_are_known() {
+        let metadata: Vec<(&str, Option<&str>)> = [
+            "version",
+            "description",
+            "repository",
+            "homepage",
+            "documentation",
+            "authors",
+        ]
+        .iter()
+        .map(|&name| (name, None))
+        .collect();
+        let tags: Vec<&str> = super::TEMPLATE_TAGS.iter().map(|&(tag, _)| tag).collect();
+
+        assert!(super::find_unknown_tags(&tags.join("\n"), &metadata).is_empty());
+    }
+
+    #[test]
+    fn template_uses_crate_info() {
+        assert!(!super::uses_crate_info("{{toc}}\n\n{{readme}}\n\n{{date}}"));
+        assert!(super::uses_crate_info("# {{crate}}\n\n{{readme}}"));
+        assert!(super::uses_crate_info("{{readme}}\n\n{{repository}}"));
+        assert!(super::uses_crate_info(
+            "{{readme}}{{#if license}}MIT{{/if}}"
+        ));
+    }
+
+    #[test]
+    fn template_with_description() {
+        let result = super::process_template(
+            TEMPLATE_WITH_DESCRIPTION.to_owned(),
+            "readme".to_owned(),
+            &CrateInfo {
+                metadata: &[("description", Some("description"))],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+        );
+        assert!(result.is_ok());
+        assert_eq!("description\n\nreadme", result.unwrap());
+    }
+
+    #[test]
+    fn template_with_metadata() {
+        let result = super::process_template(
+            TEMPLATE_WITH_METADATA.to_owned(),
+            "readme".to_owned(),
+            &CrateInfo {
+                metadata: &[
+                    ("repository", Some("https://github.com/me/my-crate")),
+                    ("homepage", Some("https://my-crate.rs")),
+                    ("documentation", Some("https://docs.rs/my-crate")),
+                    ("authors", Some("Me <me@example.com>, You")),
+                ],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            "readme\n\nhttps://github.com/me/my-crate\nhttps://my-crate.rs\n\
+             https://docs.rs/my-crate\nMe <me@example.com>, You",
+            result.unwrap()
+        );
+    }
+
     #[test]
     fn template_full() {
         let result = super::process_template(
             TEMPLATE_FULL.to_owned(),
             "readme".to_owned(),
-            "title",
-            &["badge1", "badge2"],
-            Some("license"),
-            "3.0.2",
+            &CrateInfo {
+                title: "title",
+                badges: &["badge1", "badge2"],
+                license: Some("license"),
+                metadata: &[("version", Some("3.0.2"))],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -285,18 +1068,111 @@ mod tests {
         );
     }
 
+    #[test]
+    fn template_with_license_condition_and_license() {
+        let result = super::process_template(
+            TEMPLATE_WITH_LICENSE_CONDITION.to_owned(),
+            "readme".to_owned(),
+            &CrateInfo {
+                license: Some("MIT"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+        );
+        assert_eq!(
+            Ok("readme\n\n## License\n\nMIT\n\nThe end".to_owned()),
+            result
+        );
+    }
+
+    #[test]
+    fn template_with_license_condition_without_license() {
+        let result = super::process_template(
+            TEMPLATE_WITH_LICENSE_CONDITION.to_owned(),
+            "readme".to_owned(),
+            &CrateInfo::default(),
+            &ReadmeOptions::new(),
+        );
+        assert_eq!(Ok("readme\n\nThe end".to_owned()), result);
+    }
+
+    #[test]
+    fn template_with_inline_conditions() {
+        let result = super::process_template(
+            "{{readme}}{{#if version}} v{{version}}{{/if}}{{#if description}}: {{description}}{{/if}}"
+                .to_owned(),
+            "readme".to_owned(),
+            &CrateInfo {
+                metadata: &[("version", Some("1.0.0")), ("description", None)],
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+        );
+        assert_eq!(Ok("readme v1.0.0".to_owned()), result);
+    }
+
+    #[test]
+    fn template_with_invalid_condition_should_fail() {
+        for &(template, message) in &[
+            (
+                "{{readme}}{{#if crate}}{{crate}}{{/if}}",
+                "Unsupported condition `{{#if crate}}` in template, expected `license`, \
+                 `description` or `version`",
+            ),
+            (
+                "{{readme}}{{#if license}}{{license}}",
+                "Missing `{{/if}}` after `{{#if}}` in template",
+            ),
+            (
+                "{{readme}}{{license}}{{/if}}",
+                "Found `{{/if}}` without `{{#if}}` in template",
+            ),
+            (
+                "{{readme}}{{#if license}}{{#if version}}{{/if}}{{/if}}",
+                "Nested `{{#if}}` blocks are not supported in template",
+            ),
+        ] {
+            let result = super::process_template(
+                template.to_owned(),
+                "readme".to_owned(),
+                &CrateInfo {
+                    license: Some("MIT"),
+                    metadata: &[("version", Some("1.0.0"))],
+                    ..Default::default()
+                },
+                &ReadmeOptions::new(),
+            );
+            assert_eq!(Err(message.to_owned()), result);
+        }
+    }
+
     // process string
     #[test]
     fn render_minimal() {
-        let result = super::process_string("readme".to_owned(), "", &[], None, false, false, false);
+        let result = super::process_string(
+            "readme".to_owned(),
+            &CrateInfo::default(),
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false),
+            None,
+        );
         assert!(result.is_ok());
         assert_eq!("readme", result.unwrap());
     }
 
     #[test]
     fn render_title() {
-        let result =
-            super::process_string("readme".to_owned(), "title", &[], None, true, false, false);
+        let result = super::process_string(
+            "readme".to_owned(),
+            &CrateInfo {
+                title: "title",
+                ..Default::default()
+            },
+            &ReadmeOptions::new().add_badges(false).add_license(false),
+            None,
+        );
         assert!(result.is_ok());
         assert_eq!("# title\n\nreadme", result.unwrap());
     }
@@ -305,12 +1181,12 @@ mod tests {
     fn render_badges() {
         let result = super::process_string(
             "readme".to_owned(),
-            "",
-            &["badge1", "badge2"],
+            &CrateInfo {
+                badges: &["badge1", "badge2"],
+                ..Default::default()
+            },
+            &ReadmeOptions::new().add_title(false).add_license(false),
             None,
-            false,
-            true,
-            false,
         );
         assert!(result.is_ok());
         assert_eq!("badge1\nbadge2\n\nreadme", result.unwrap());
@@ -320,12 +1196,12 @@ mod tests {
     fn render_license() {
         let result = super::process_string(
             "readme".to_owned(),
-            "",
-            &[],
-            Some("license"),
-            false,
-            false,
-            true,
+            &CrateInfo {
+                license: Some("license"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new().add_title(false).add_badges(false),
+            None,
         );
         assert!(result.is_ok());
         assert_eq!("readme\n\nLicense: license", result.unwrap());
@@ -335,12 +1211,14 @@ mod tests {
     fn render_full() {
         let result = super::process_string(
             "readme".to_owned(),
-            "title",
-            &["badge1", "badge2"],
-            Some("license"),
-            true,
-            true,
-            true,
+            &CrateInfo {
+                title: "title",
+                badges: &["badge1", "badge2"],
+                license: Some("license"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new(),
+            None,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -353,12 +1231,93 @@ mod tests {
     fn render_nothing() {
         let result = super::process_string(
             "readme".to_owned(),
-            "title",
-            &["badge1", "badge2"],
-            Some("license"),
-            false,
-            false,
-            false,
+            &CrateInfo {
+                title: "title",
+                badges: &["badge1", "badge2"],
+                license: Some("license"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new()
+                .add_title(false)
+                .add_badges(false)
+                .add_license(false),
+            None,
+        );
+        assert!(result.is_ok());
+        assert_eq!("readme", result.unwrap());
+    }
+
+    #[test]
+    fn render_license_with_license_section_in_docs() {
+        let result = super::process_string(
+            "readme\n\n## License\n\nMIT or Apache-2.0".to_owned(),
+            &CrateInfo {
+                license: Some("MIT OR Apache-2.0"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new().add_title(false).add_badges(false),
+            None,
+        );
+        assert!(result.is_ok());
+        assert_eq!("readme\n\n## License\n\nMIT or Apache-2.0", result.unwrap());
+    }
+
+    #[test]
+    fn render_license_body() {
+        let result = super::process_string(
+            "readme".to_owned(),
+            &CrateInfo {
+                license: Some("MIT"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new().add_title(false).add_badges(false),
+            Some("MIT License\n\nCopyright (c) me\n"),
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            "readme\n\n## License\n\nMIT License\n\nCopyright (c) me",
+            result.unwrap()
+        );
+    }
+
+    #[test]
+    fn render_license_file() {
+        let result = super::process_string(
+            "readme".to_owned(),
+            &CrateInfo {
+                license_file: Some("LICENSE.txt"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new().add_title(false).add_badges(false),
+            None,
+        );
+        assert!(result.is_ok());
+        assert_eq!("readme\n\nLicense: see LICENSE.txt", result.unwrap());
+    }
+
+    #[test]
+    fn render_license_before_license_file() {
+        let result = super::process_string(
+            "readme".to_owned(),
+            &CrateInfo {
+                license: Some("MIT"),
+                license_file: Some("LICENSE.txt"),
+                ..Default::default()
+            },
+            &ReadmeOptions::new().add_title(false).add_badges(false),
+            None,
+        );
+        assert!(result.is_ok());
+        assert_eq!("readme\n\nLicense: MIT", result.unwrap());
+    }
+
+    #[test]
+    fn render_without_license() {
+        let result = super::process_string(
+            "readme".to_owned(),
+            &CrateInfo::default(),
+            &ReadmeOptions::new().add_title(false).add_badges(false),
+            None,
         );
         assert!(result.is_ok());
         assert_eq!("readme", result.unwrap());
@@ -392,26 +1351,260 @@ mod tests {
     // prepend title
     #[test]
     fn prepend_title_with_filled_readme() {
-        let result = super::prepend_title("readme".into(), "title");
+        let result = super::prepend_title("readme".into(), "title", "# {name}\n\n");
         assert_eq!("# title\n\nreadme", result);
     }
 
+    #[test]
+    fn title_case_crate_name() {
+        assert_eq!("My Cool Crate", super::title_case_name("my-cool_crate"));
+        assert_eq!("Crate", super::title_case_name("crate"));
+        assert_eq!("Already Cased", super::title_case_name("Already-Cased"));
+        assert_eq!("Double Dash", super::title_case_name("double--dash"));
+    }
+
+    #[test]
+    fn prepend_title_with_custom_format() {
+        let result = super::prepend_title("readme".into(), "title", "# {name}\n");
+        assert_eq!("# title\nreadme", result);
+
+        let result = super::prepend_title("readme".into(), "title", "{name}\n=====\n\n");
+        assert_eq!("title\n=====\n\nreadme", result);
+    }
+
     #[test]
     fn prepend_title_with_empty_readme() {
-        let result = super::prepend_title("".into(), "title");
+        let result = super::prepend_title("".into(), "title", "# {name}\n\n");
         assert_eq!("# title", result);
     }
 
     // append license
     #[test]
     fn append_license_with_filled_readme() {
-        let result = super::append_license("readme".into(), "license");
+        let result =
+            super::append_license("readme".into(), "license", "License: ", false, false, None);
         assert_eq!("readme\n\nLicense: license", result);
     }
 
     #[test]
     fn append_license_with_empty_readme() {
-        let result = super::append_license("".into(), "license");
+        let result = super::append_license("".into(), "license", "License: ", false, false, None);
         assert_eq!("License: license", result);
     }
+
+    #[test]
+    fn append_license_with_custom_prefix() {
+        let result = super::append_license("readme".into(), "MIT", "Lizenz: ", false, false, None);
+        assert_eq!("readme\n\nLizenz: MIT", result);
+
+        let result =
+            super::append_license("readme".into(), "MIT", "## License\n\n", false, false, None);
+        assert_eq!("readme\n\n## License\n\nMIT", result);
+    }
+
+    #[test]
+    fn append_license_as_prose() {
+        let result = super::append_license(
+            "readme".into(),
+            "MIT OR Apache-2.0",
+            "License: ",
+            true,
+            false,
+            None,
+        );
+        assert_eq!(
+            "readme\n\nLicensed under either of MIT or Apache-2.0",
+            result
+        );
+    }
+
+    #[test]
+    fn append_license_with_heading() {
+        let result =
+            super::append_license("readme".into(), "MIT", "License: ", false, false, Some(2));
+        assert_eq!("readme\n\n## License\n\nMIT", result);
+    }
+
+    #[test]
+    fn append_license_as_prose_with_heading() {
+        let result = super::append_license(
+            "readme".into(),
+            "MIT OR Apache-2.0",
+            "License: ",
+            true,
+            false,
+            Some(1),
+        );
+        assert_eq!(
+            "readme\n\n# License\n\nLicensed under either of MIT or Apache-2.0",
+            result
+        );
+    }
+
+    #[test]
+    fn append_license_file_inline() {
+        let result = super::append_license_file("readme".into(), "LICENSE.txt", "License: ", None);
+        assert_eq!("readme\n\nLicense: see LICENSE.txt", result);
+    }
+
+    #[test]
+    fn append_license_file_with_custom_prefix() {
+        let result = super::append_license_file("readme".into(), "LICENSE.txt", "Lizenz: ", None);
+        assert_eq!("readme\n\nLizenz: see LICENSE.txt", result);
+    }
+
+    #[test]
+    fn append_license_file_with_heading() {
+        let result =
+            super::append_license_file("readme".into(), "LICENSE.txt", "License: ", Some(2));
+        assert_eq!("readme\n\n## License\n\nSee LICENSE.txt", result);
+    }
+
+    // append keywords and categories
+    #[test]
+    fn append_keywords_and_categories() {
+        let keywords = vec!["readme".to_owned(), "docs".to_owned()];
+        let categories = vec!["development-tools".to_owned()];
+        let result = super::append_keywords_and_categories("readme".into(), &keywords, &categories);
+        assert_eq!(
+            "readme\n\nKeywords: readme, docs\n\nCategories: development-tools",
+            result
+        );
+    }
+
+    #[test]
+    fn append_keywords_only() {
+        let keywords = vec!["readme".to_owned()];
+        let result = super::append_keywords_and_categories("readme".into(), &keywords, &[]);
+        assert_eq!("readme\n\nKeywords: readme", result);
+    }
+
+    #[test]
+    fn append_empty_keywords_and_categories() {
+        let result = super::append_keywords_and_categories("readme".into(), &[], &[]);
+        assert_eq!("readme", result);
+    }
+
+    // format license
+    #[test]
+    fn format_single_license() {
+        assert_eq!(
+            "License: MIT",
+            super::format_license("MIT", "License: ", false, false)
+        );
+        assert_eq!(
+            "License: MIT",
+            super::format_license("MIT", "License: ", true, false)
+        );
+    }
+
+    #[test]
+    fn format_or_license_expression() {
+        assert_eq!(
+            "License: MIT OR Apache-2.0",
+            super::format_license("MIT OR Apache-2.0", "License: ", false, false)
+        );
+        assert_eq!(
+            "Licensed under either of MIT or Apache-2.0",
+            super::format_license("MIT OR Apache-2.0", "License: ", true, false)
+        );
+        assert_eq!(
+            "Licensed under either of MIT, Apache-2.0 or Zlib",
+            super::format_license("MIT OR Apache-2.0 OR Zlib", "License: ", true, false)
+        );
+    }
+
+    #[test]
+    fn format_and_license_expression() {
+        assert_eq!(
+            "Licensed under all of MIT and Unicode-DFS-2016",
+            super::format_license("MIT AND Unicode-DFS-2016", "License: ", true, false)
+        );
+    }
+
+    // link licenses
+    #[test]
+    fn link_single_license() {
+        assert_eq!(
+            "[MIT](https://spdx.org/licenses/MIT.html)",
+            super::link_licenses("MIT")
+        );
+        assert_eq!(
+            "[GPL-2.0+](https://spdx.org/licenses/GPL-2.0+.html)",
+            super::link_licenses("GPL-2.0+")
+        );
+    }
+
+    #[test]
+    fn link_license_expressions() {
+        assert_eq!(
+            "[MIT](https://spdx.org/licenses/MIT.html) OR \
+             [Apache-2.0](https://spdx.org/licenses/Apache-2.0.html)",
+            super::link_licenses("MIT OR Apache-2.0")
+        );
+        assert_eq!(
+            "[MIT](https://spdx.org/licenses/MIT.html)/\
+             [Apache-2.0](https://spdx.org/licenses/Apache-2.0.html)",
+            super::link_licenses("MIT/Apache-2.0")
+        );
+        assert_eq!(
+            "([MIT](https://spdx.org/licenses/MIT.html) OR LicenseRef-Custom) AND \
+             [Apache-2.0](https://spdx.org/licenses/Apache-2.0.html) WITH \
+             [LLVM-exception](https://spdx.org/licenses/LLVM-exception.html)",
+            super::link_licenses("(MIT OR LicenseRef-Custom) AND Apache-2.0 WITH LLVM-exception")
+        );
+    }
+
+    #[test]
+    fn link_non_spdx_license() {
+        assert_eq!("see LICENSE", super::link_licenses("see LICENSE"));
+        assert_eq!("MIT OR", super::link_licenses("MIT OR"));
+        assert_eq!(
+            "Custom license, v2",
+            super::link_licenses("Custom license, v2")
+        );
+    }
+
+    #[test]
+    fn format_linked_license() {
+        assert_eq!(
+            "Licensed under either of [MIT](https://spdx.org/licenses/MIT.html) or \
+             [Apache-2.0](https://spdx.org/licenses/Apache-2.0.html)",
+            super::format_license("MIT OR Apache-2.0", "License: ", true, true)
+        );
+        assert_eq!(
+            "License: [MIT](https://spdx.org/licenses/MIT.html) OR \
+             [Apache-2.0](https://spdx.org/licenses/Apache-2.0.html)",
+            super::format_license("MIT OR Apache-2.0", "License: ", false, true)
+        );
+        assert_eq!(
+            "Released under [MIT](https://spdx.org/licenses/MIT.html)",
+            super::format_license("MIT", "Released under ", false, true)
+        );
+        assert_eq!(
+            "License: see LICENSE",
+            super::format_license("see LICENSE", "License: ", true, true)
+        );
+        assert_eq!(
+            "## License\n\n[Zlib](https://spdx.org/licenses/Zlib.html)",
+            super::format_license_section("Zlib", false, true, 2)
+        );
+    }
+
+    #[test]
+    fn format_complex_license_expression() {
+        assert_eq!(
+            "License: (MIT OR Apache-2.0) AND Unicode-DFS-2016",
+            super::format_license(
+                "(MIT OR Apache-2.0) AND Unicode-DFS-2016",
+                "License: ",
+                true,
+                false
+            )
+        );
+        assert_eq!(
+            "License: MIT OR Apache-2.0 AND Zlib",
+            super::format_license("MIT OR Apache-2.0 AND Zlib", "License: ", true, false)
+        );
+    }
 }
@@ -0,0 +1,266 @@
+//! Reflow prose paragraphs to a maximum width
+//!
+//! Only plain paragraphs are wrapped. Code blocks, headings, lists, tables, quotes and HTML are
+//! kept as they are, since their line breaks are significant or their indentation would be lost.
+
+use std::mem;
+
+use regex::Regex;
+
+use super::fence::FenceTracker;
+
+lazy_static! {
+    // Line that is not plain paragraph text: heading, setext heading underline, thematic break,
+    // list item, table row, quote or HTML
+    static ref RE_NOT_PROSE: Regex = Regex::new(r"^(?:#|(?:=+|-+|(?:[-*_] *){3,})\s*$|[-*+](?: |$)|\d+[.)](?: |$)|\||>|<)").unwrap();
+    // Reference link definition, like "[id]: https://..."
+    static ref RE_DEFINITION: Regex = Regex::new(r"^\[[^\]]+\]:").unwrap();
+}
+
+/// Wrap the paragraphs of the docs so the lines are at most `width` columns
+///
+/// Paragraphs are the blocks of lines between blank lines. A paragraph is only wrapped if all its
+/// lines are plain text, not indented and without hard line breaks. Words longer than `width`
+/// are not split, so they are left on their own line, and inline code is kept as one word. A
+/// word which would turn the next line into a list item, heading, quote or table row, like "-" or
+/// "2.", stays on the current line.
+pub fn wrap_paragraphs(lines: Vec<String>, width: usize) -> Vec<String> {
+    let mut fence = FenceTracker::new();
+    let mut result = Vec::new();
+    let mut paragraph: Vec<String> = Vec::new();
+
+    for line in lines {
+        if fence.is_code(&line) || line.trim().is_empty() {
+            result.extend(wrap_paragraph(mem::take(&mut paragraph), width));
+            result.push(line);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    result.extend(wrap_paragraph(paragraph, width));
+
+    result
+}
+
+/// Wrap a single paragraph, if it is plain text
+fn wrap_paragraph(paragraph: Vec<String>, width: usize) -> Vec<String> {
+    if paragraph.is_empty() || !paragraph.iter().all(|line| is_prose(line)) {
+        return paragraph;
+    }
+
+    let text = paragraph
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    let mut result = Vec::new();
+    let mut current = String::new();
+
+    for word in split_words(&text) {
+        if !current.is_empty()
+            && current.chars().count() + 1 + word.chars().count() > width
+            && !RE_NOT_PROSE.is_match(word)
+        {
+            result.push(current);
+            current = String::new();
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    result.push(current);
+
+    result
+}
+
+/// Split the text on whitespace, except inside inline code, whose spaces are significant
+///
+/// A code span starts with a run of backticks and ends with the next run of the same length. A
+/// run without a matching one is a literal backtick.
+fn split_words(text: &str) -> Vec<&str> {
+    let mut runs = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '`' {
+            let mut end = start + 1;
+            while let Some(&(i, '`')) = chars.peek() {
+                end = i + 1;
+                chars.next();
+            }
+            runs.push((start, end));
+        }
+    }
+
+    let mut code_spans = Vec::new();
+    let mut i = 0;
+    while i < runs.len() {
+        let (start, end) = runs[i];
+        let closing = runs[i + 1..]
+            .iter()
+            .position(|&(s, e)| e - s == end - start);
+        match closing {
+            Some(offset) => {
+                code_spans.push((start, runs[i + 1 + offset].1));
+                i += offset + 2;
+            }
+            None => i += 1,
+        }
+    }
+
+    let in_code_span = |index: usize| {
+        code_spans
+            .iter()
+            .any(|&(start, end)| start <= index && index < end)
+    };
+
+    let mut words = Vec::new();
+    let mut word_start = None;
+    for (index, c) in text.char_indices() {
+        if c.is_whitespace() && !in_code_span(index) {
+            if let Some(start) = word_start.take() {
+                words.push(&text[start..index]);
+            }
+        } else if word_start.is_none() {
+            word_start = Some(index);
+        }
+    }
+    if let Some(start) = word_start {
+        words.push(&text[start..]);
+    }
+
+    words
+}
+
+/// Can this line be part of a wrapped paragraph?
+fn is_prose(line: &str) -> bool {
+    !line.starts_with(char::is_whitespace)
+        && !line.ends_with("  ")
+        && !line.ends_with('\\')
+        && !RE_NOT_PROSE.is_match(line)
+        && !RE_DEFINITION.is_match(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wrap_paragraphs;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn wrap_long_paragraph() {
+        let docs = lines(&[
+            "The quick brown fox jumps over the lazy dog, then the dog wakes up and chases the fox",
+            "around the yard.",
+            "",
+            "Short paragraph.",
+        ]);
+        assert_eq!(
+            wrap_paragraphs(docs, 30),
+            &[
+                "The quick brown fox jumps over",
+                "the lazy dog, then the dog",
+                "wakes up and chases the fox",
+                "around the yard.",
+                "",
+                "Short paragraph.",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_joins_short_lines() {
+        let docs = lines(&["First", "second", "third."]);
+        assert_eq!(wrap_paragraphs(docs, 80), &["First second third."]);
+    }
+
+    #[test]
+    fn wrap_keeps_long_words() {
+        let docs = lines(&["see https://example.com/a/very/long/url for details"]);
+        assert_eq!(
+            wrap_paragraphs(docs, 10),
+            &[
+                "see",
+                "https://example.com/a/very/long/url",
+                "for",
+                "details"
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_skips_code_blocks() {
+        let docs = lines(&[
+            "Some text before the code block that is long enough to wrap",
+            "```rust",
+            "let long_line = \"this line of code is much longer than the width and is kept\";",
+            "```",
+        ]);
+        assert_eq!(
+            wrap_paragraphs(docs, 40),
+            &[
+                "Some text before the code block that is",
+                "long enough to wrap",
+                "```rust",
+                "let long_line = \"this line of code is much longer than the width and is kept\";",
+                "```",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_skips_headings_lists_and_tables() {
+        let input = &[
+            "## A heading that is longer than the width",
+            "",
+            "- a list item that is longer than the width",
+            "  with a continuation line",
+            "",
+            "1. a numbered item that is longer than the width",
+            "",
+            "| a | table | row | that | is | longer | than | the | width |",
+            "",
+            "> a quote that is longer than the width",
+        ];
+        assert_eq!(wrap_paragraphs(lines(input), 20), input);
+    }
+
+    #[test]
+    fn wrap_keeps_list_markers_on_the_line() {
+        let docs = lines(&["Some steps - more", "", "in step 2. then"]);
+        assert_eq!(
+            wrap_paragraphs(docs, 10),
+            &["Some steps -", "more", "", "in step 2.", "then"]
+        );
+    }
+
+    #[test]
+    fn wrap_keeps_spaces_in_inline_code() {
+        let docs = lines(&["Call `a  +  b` or ``x ` y`` with a `single", "tick"]);
+        assert_eq!(
+            wrap_paragraphs(docs, 12),
+            &[
+                "Call",
+                "`a  +  b` or",
+                "``x ` y``",
+                "with a",
+                "`single tick"
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_skips_setext_headings_and_breaks() {
+        let input = &["Examples", "--------", "", "Some text", "=====", "", "***"];
+        assert_eq!(wrap_paragraphs(lines(input), 80), input);
+    }
+
+    #[test]
+    fn wrap_skips_hard_line_breaks() {
+        let input = &["a line with a hard break  ", "next line"];
+        assert_eq!(wrap_paragraphs(lines(input), 10), input);
+    }
+}
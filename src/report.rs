@@ -0,0 +1,120 @@
+//! Machine readable report of what was done, printed with `--message-format json`
+//!
+//! The report is a single JSON object on one line. It only holds strings, numbers and nulls, so it
+//! is written by hand.
+
+use std::path::{Path, PathBuf};
+
+use cargo_readme::project;
+
+/// The files read and written to generate the readme
+pub struct Report {
+    /// The source files, `None` for stdin
+    pub inputs: Vec<Option<PathBuf>>,
+    /// The output file, `None` if nothing was written
+    pub output: Option<PathBuf>,
+    /// The template file, if any
+    pub template: Option<PathBuf>,
+    pub bytes_written: usize,
+}
+
+impl Report {
+    /// Format the report as a JSON object
+    ///
+    /// `input` is a string, or a list of strings when the docs are read from several sources.
+    /// Stdin is reported as `-`.
+    pub fn to_json(&self) -> String {
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|input| match *input {
+                Some(ref path) => json_path(path),
+                None => json_string("-"),
+            })
+            .collect();
+        let input = if inputs.len() == 1 {
+            inputs[0].clone()
+        } else {
+            format!("[{}]", inputs.join(", "))
+        };
+
+        format!(
+            "{{\"input\": {}, \"output\": {}, \"template\": {}, \"bytes_written\": {}}}",
+            input,
+            json_optional_path(self.output.as_ref()),
+            json_optional_path(self.template.as_ref()),
+            self.bytes_written
+        )
+    }
+}
+
+fn json_optional_path(path: Option<&PathBuf>) -> String {
+    path.map_or("null".to_owned(), |path| json_path(path))
+}
+
+fn json_path(path: &Path) -> String {
+    json_string(&project::display_path(path))
+}
+
+/// Quote and escape a string for JSON
+fn json_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c < ' ' => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{json_string, Report};
+
+    #[test]
+    fn escape_json_string() {
+        assert_eq!(r#""README.md""#, json_string("README.md"));
+        assert_eq!(
+            r#""a \"quoted\" \\ path\n\u0001""#,
+            json_string("a \"quoted\" \\ path\n\u{1}")
+        );
+    }
+
+    #[test]
+    fn report_single_input() {
+        let report = Report {
+            inputs: vec![Some(PathBuf::from("/project/src/lib.rs"))],
+            output: Some(PathBuf::from("/project/README.md")),
+            template: None,
+            bytes_written: 42,
+        };
+        assert_eq!(
+            r#"{"input": "/project/src/lib.rs", "output": "/project/README.md", "template": null, "bytes_written": 42}"#,
+            report.to_json()
+        );
+    }
+
+    #[test]
+    fn report_several_inputs() {
+        let report = Report {
+            inputs: vec![Some(PathBuf::from("/project/src/lib.rs")), None],
+            output: None,
+            template: Some(PathBuf::from("/project/README.tpl")),
+            bytes_written: 0,
+        };
+        assert_eq!(
+            r#"{"input": ["/project/src/lib.rs", "-"], "output": null, "template": "/project/README.tpl", "bytes_written": 0}"#,
+            report.to_json()
+        );
+    }
+}
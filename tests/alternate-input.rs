@@ -12,6 +12,8 @@ fn alternate_input_empty_docs() {
         "--no-badges",
         "--input",
         "src/no_docs.rs",
+        "--allow-empty",
+        "--stdout",
     ];
 
     Assert::main_binary()
@@ -23,6 +25,28 @@ fn alternate_input_empty_docs() {
         .unwrap();
 }
 
+#[test]
+fn alternate_input_empty_docs_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--input",
+        "src/no_docs.rs",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .is("Error: No doc comments found in the source file")
+        .unwrap();
+}
+
 #[test]
 fn alternate_input_single_line() {
     let args = [
@@ -33,6 +57,7 @@ fn alternate_input_single_line() {
         "--no-badges",
         "--input",
         "src/single_line.rs",
+        "--stdout",
     ];
 
     let expected = r#"
@@ -62,6 +87,119 @@ fn alternate_input_a_little_bit_longer() {
         "--no-badges",
         "--input",
         "src/other.rs",
+        "--stdout",
+    ];
+
+    let expected = r#"
+# readme-test
+
+Test crate for cargo-readme
+
+## Level 1 heading should become level 2
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(expected)
+        .unwrap();
+}
+
+#[test]
+fn alternate_input_multiple_files() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--input",
+        "src/single_line.rs",
+        "--input",
+        "src/other.rs",
+        "--stdout",
+    ];
+
+    let expected = r#"
+# readme-test
+
+Test crate for cargo-readme
+
+Test crate for cargo-readme
+
+## Level 1 heading should become level 2
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(expected)
+        .unwrap();
+}
+
+#[test]
+fn alternate_input_multiple_files_with_separator() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--input",
+        "src/single_line.rs",
+        "--input",
+        "src/other.rs",
+        "--input-separator",
+        "---",
+        "--stdout",
+    ];
+
+    let expected = r#"
+# readme-test
+
+Test crate for cargo-readme
+
+---
+
+Test crate for cargo-readme
+
+## Level 1 heading should become level 2
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(expected)
+        .unwrap();
+}
+
+#[test]
+fn alternate_input_absolute_path() {
+    let input = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/test-project/src/other.rs"
+    );
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--input",
+        input,
+        "--stdout",
     ];
 
     let expected = r#"
@@ -82,3 +220,79 @@ License: MIT
         .is(expected)
         .unwrap();
 }
+
+#[test]
+fn alternate_input_missing_file_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/missing.rs",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("Could not open file '")
+        .and()
+        .stderr()
+        .contains("tests/test-project/src/missing.rs': ")
+        .and()
+        .stderr()
+        .contains("(os error 2)")
+        .unwrap();
+}
+
+#[test]
+fn alternate_input_glob() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--input-glob",
+        "src/single_*.rs",
+        "--stdout",
+    ];
+
+    let expected = r#"
+# readme-test
+
+Test crate for cargo-readme
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(expected)
+        .unwrap();
+}
+
+#[test]
+fn alternate_input_glob_multiple_matches_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input-glob",
+        "src/**/*.rs",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("Error: More than one file matches 'src/**/*.rs': src/lib.rs, src/multiline.rs")
+        .unwrap();
+}
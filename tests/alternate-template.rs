@@ -1,6 +1,6 @@
 extern crate assert_cli;
 
-use assert_cli::Assert;
+use assert_cli::{Assert, Environment};
 
 const EXPECTED: &str = r#"
 # readme-test
@@ -54,6 +54,7 @@ fn alternate_template() {
         "tests/test-project",
         "--template",
         "NOTITLE.tpl",
+        "--stdout",
     ];
 
     Assert::main_binary()
@@ -64,3 +65,86 @@ fn alternate_template() {
         .is(EXPECTED)
         .unwrap();
 }
+
+#[test]
+fn template_with_toc() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "TOC.tpl",
+        "--stdout",
+    ];
+
+    let toc = "- [Level 1 heading should become level 2](#level-1-heading-should-become-level-2)\n  \
+               - [Level 2 heading should become level 3](#level-2-heading-should-become-level-3)\n    \
+               - [Level 3 heading should become level 4](#level-3-heading-should-become-level-4)\n";
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains(&*format!(
+            "# readme-test\n\n{}\nTest crate for cargo-readme",
+            toc
+        ))
+        .unwrap();
+}
+
+#[test]
+fn template_from_env() {
+    let args = ["readme", "--project-root", "tests/test-project", "--stdout"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .with_env(Environment::inherit().insert("CARGO_README_TEMPLATE", "NOTITLE.tpl"))
+        .succeeds()
+        .and()
+        .stdout()
+        .is(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn template_option_before_env() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "OTHER.tpl",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .with_env(Environment::inherit().insert("CARGO_README_TEMPLATE", "NOTITLE.tpl"))
+        .succeeds()
+        .and()
+        .stdout()
+        .doesnt_contain("# readme-test")
+        .unwrap();
+}
+
+#[test]
+fn no_template_ignores_env() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .with_env(Environment::inherit().insert("CARGO_README_TEMPLATE", "NOTITLE.tpl"))
+        .succeeds()
+        .and()
+        .stdout()
+        .doesnt_contain("Other readme template.")
+        .unwrap();
+}
@@ -52,6 +52,7 @@ fn append_license() {
         "tests/test-project",
         "--no-template",
         "--no-badges",
+        "--stdout",
     ];
 
     let expected = format!("{}\n\n{}", EXPECTED.trim(), "License: MIT");
@@ -74,6 +75,7 @@ fn no_append_license() {
         "--no-template",
         "--no-badges",
         "--no-license",
+        "--stdout",
     ];
 
     Assert::main_binary()
@@ -84,3 +86,103 @@ fn no_append_license() {
         .is(EXPECTED)
         .unwrap();
 }
+
+#[test]
+fn append_license_with_custom_prefix() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--license-prefix",
+        "Lizenz: ",
+        "--stdout",
+    ];
+
+    let expected = format!("{}\n\n{}", EXPECTED.trim(), "Lizenz: MIT");
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(&*expected)
+        .unwrap();
+}
+
+#[test]
+fn append_license_with_link() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--license-prefix",
+        "Lizenz: ",
+        "--license-link",
+        "--stdout",
+    ];
+
+    let expected = format!(
+        "{}\n\n{}",
+        EXPECTED.trim(),
+        "Lizenz: [MIT](https://spdx.org/licenses/MIT.html)"
+    );
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(&*expected)
+        .unwrap();
+}
+
+#[test]
+fn license_prefix_conflicts_with_prose() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--license-prefix",
+        "Lizenz: ",
+        "--license-prose",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("cannot be used with")
+        .unwrap();
+}
+
+#[test]
+fn license_prefix_conflicts_with_linked_prose() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--license-prefix",
+        "Lizenz: ",
+        "--license-link",
+        "--license-prose",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("`--license-prefix` cannot be used with `--license-prose`")
+        .unwrap();
+}
@@ -0,0 +1,61 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn append_metadata() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--append-metadata",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("License: MIT\n\nKeywords: readme, test\n\nCategories: development-tools\n")
+        .unwrap();
+}
+
+#[test]
+fn append_metadata_without_keywords_and_categories() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--append-metadata",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("# check-test\n\nTest crate for cargo-readme `--check`\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn no_append_metadata() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .doesnt_contain("Keywords:")
+        .unwrap();
+}
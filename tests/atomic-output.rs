@@ -0,0 +1,32 @@
+extern crate assert_cli;
+
+use std::fs;
+use std::path::Path;
+
+use assert_cli::Assert;
+
+const EXISTING: &str = "# atomic-output\n\nExisting readme\n";
+
+#[test]
+fn failed_generation_keeps_existing_readme() {
+    // the template needs badges, which the crate does not have
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/atomic-output",
+        "--template",
+        "BADGES.tpl",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("`{{badges}}` was found in template but no badges were provided")
+        .unwrap();
+
+    let result = fs::read_to_string("tests/atomic-output/README.md").unwrap();
+    assert_eq!(EXISTING, result);
+    assert!(!Path::new("tests/atomic-output/.README.md.cargo-readme.tmp").exists());
+}
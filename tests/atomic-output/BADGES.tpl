@@ -0,0 +1,3 @@
+{{badges}}
+
+{{readme}}
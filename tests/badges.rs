@@ -21,7 +21,7 @@ License: MIT
 
 #[test]
 fn badges() {
-    let args = ["readme", "--project-root", "tests/badges"];
+    let args = ["readme", "--project-root", "tests/badges", "--stdout"];
 
     Assert::main_binary()
         .with_args(&args)
@@ -0,0 +1,170 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED_DIFF: &str = "--- STALE.md
++++ STALE.md (generated)
+@@ -1,5 +1,5 @@
+ # check-test
+ \n-Outdated description
++Test crate for cargo-readme `--check`
+ \n License: MIT";
+
+#[test]
+fn check_up_to_date() {
+    let args = ["readme", "--project-root", "tests/check", "--check"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("")
+        .unwrap();
+}
+
+#[test]
+fn check_out_of_date() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--output",
+        "STALE.md",
+        "--check",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stdout()
+        .contains(EXPECTED_DIFF)
+        .and()
+        .stderr()
+        .contains("is not up to date")
+        .unwrap();
+}
+
+#[test]
+fn check_out_of_date_color_never() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--output",
+        "STALE.md",
+        "--check",
+        "--color",
+        "never",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stdout()
+        .contains(EXPECTED_DIFF)
+        .and()
+        .stdout()
+        .doesnt_contain("\x1b[")
+        .unwrap();
+}
+
+#[test]
+fn check_out_of_date_color_always() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--output",
+        "STALE.md",
+        "--check",
+        "--color",
+        "always",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stdout()
+        .contains("\x1b[31m-Outdated description\x1b[0m")
+        .and()
+        .stdout()
+        .contains("\x1b[32m+Test crate for cargo-readme `--check`\x1b[0m")
+        .unwrap();
+}
+
+#[test]
+fn check_missing_newline_at_end() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--output",
+        "NO_NEWLINE.md",
+        "--check",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stdout()
+        .contains("-License: MIT\n\\ No newline at end of file\n+License: MIT\n")
+        .unwrap();
+}
+
+#[test]
+fn check_line_endings() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--output",
+        "CRLF.md",
+        "--check",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stdout()
+        .contains("\\ Only the line endings differ")
+        .unwrap();
+}
+
+#[test]
+fn diff_up_to_date() {
+    let args = ["readme", "--project-root", "tests/check", "--diff"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("")
+        .unwrap();
+}
+
+#[test]
+fn diff_out_of_date() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--output",
+        "STALE.md",
+        "--diff",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains(EXPECTED_DIFF)
+        .unwrap();
+}
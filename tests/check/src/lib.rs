@@ -0,0 +1 @@
+//! Test crate for cargo-readme `--check`
@@ -50,7 +50,7 @@ if condition {
 
 #[test]
 fn default_behavior() {
-    let args = ["readme", "--project-root", "tests/test-project"];
+    let args = ["readme", "--project-root", "tests/test-project", "--stdout"];
 
     Assert::main_binary()
         .with_args(&args)
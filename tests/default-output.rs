@@ -0,0 +1,33 @@
+extern crate assert_cli;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = "# default-output
+
+Test crate for cargo-readme default output
+
+License: MIT
+";
+
+#[test]
+fn default_output_writes_readme() {
+    let readme = "tests/default-output/README.md";
+    let _ = fs::remove_file(readme);
+
+    let args = ["readme", "--project-root", "tests/default-output"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("")
+        .unwrap();
+
+    let result = fs::read_to_string(readme).unwrap();
+    fs::remove_file(readme).unwrap();
+
+    assert_eq!(EXPECTED, result);
+}
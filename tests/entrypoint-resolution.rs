@@ -10,6 +10,7 @@ fn entrypoint_resolution_main() {
         "tests/entrypoint-resolution/main",
         "--no-title",
         "--no-license",
+        "--stdout",
     ];
 
     Assert::main_binary()
@@ -29,6 +30,7 @@ fn entrypoint_resolution_lib() {
         "tests/entrypoint-resolution/lib",
         "--no-title",
         "--no-license",
+        "--stdout",
     ];
 
     Assert::main_binary()
@@ -48,6 +50,7 @@ fn entrypoint_resolution_cargo_lib() {
         "tests/entrypoint-resolution/cargo-lib",
         "--no-title",
         "--no-license",
+        "--stdout",
     ];
 
     Assert::main_binary()
@@ -67,6 +70,7 @@ fn entrypoint_resolution_cargo_bin() {
         "tests/entrypoint-resolution/cargo-bin",
         "--no-title",
         "--no-license",
+        "--stdout",
     ];
 
     Assert::main_binary()
@@ -77,3 +81,99 @@ fn entrypoint_resolution_cargo_bin() {
         .is("cargo bin")
         .unwrap();
 }
+
+#[test]
+fn entrypoint_resolution_custom_lib() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/entrypoint-resolution/custom-lib",
+        "--no-title",
+        "--no-license",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("custom lib")
+        .unwrap();
+}
+
+#[test]
+fn entrypoint_resolution_lib_and_main_warns() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/entrypoint-resolution/lib",
+        "--no-title",
+        "--no-license",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("lib")
+        .and()
+        .stderr()
+        .contains(
+            "Warning: Found both a library (src/lib.rs) and a binary (src/bin/bin.rs), reading the \
+             docs of the library; use `--prefer bin` or `--input` to choose",
+        )
+        .unwrap();
+}
+
+#[test]
+fn entrypoint_resolution_prefer_lib() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/entrypoint-resolution/lib",
+        "--prefer",
+        "lib",
+        "--no-title",
+        "--no-license",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("lib")
+        .and()
+        .stderr()
+        .is("")
+        .unwrap();
+}
+
+#[test]
+fn entrypoint_resolution_prefer_bin() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/entrypoint-resolution/lib",
+        "--prefer",
+        "bin",
+        "--no-title",
+        "--no-license",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("cargo bin")
+        .and()
+        .stderr()
+        .is("")
+        .unwrap();
+}
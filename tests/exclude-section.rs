@@ -0,0 +1,108 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const DOCS: &str = "//! Docs
+//!
+//! # Usage
+//!
+//! Use it
+//!
+//! # Internal
+//!
+//! Not for the readme
+//!
+//! ## Details
+//!
+//! Not for the readme either
+//!
+//! # Examples
+//!
+//! An example
+";
+
+#[test]
+fn exclude_middle_section() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "-",
+        "--no-template",
+        "--no-title",
+        "--no-license",
+        "--no-badges",
+        "--exclude-section",
+        "internal",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin(DOCS)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("Docs\n\n## Usage\n\nUse it\n\n## Examples\n\nAn example")
+        .unwrap();
+}
+
+#[test]
+fn exclude_several_sections() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "-",
+        "--no-template",
+        "--no-title",
+        "--no-license",
+        "--no-badges",
+        "--exclude-section",
+        "Usage",
+        "--exclude-section",
+        "Examples",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin(DOCS)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("Docs\n\n## Internal\n\nNot for the readme\n\n### Details\n\nNot for the readme either")
+        .unwrap();
+}
+
+#[test]
+fn exclude_section_with_link_definition() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "-",
+        "--no-template",
+        "--no-title",
+        "--no-license",
+        "--no-badges",
+        "--exclude-section",
+        "links",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin("//! See [the guide][guide]\n//!\n//! # Links\n//!\n//! [guide]: docs/guide.md")
+        .succeeds()
+        .and()
+        .stdout()
+        .is("See [the guide][guide]")
+        .and()
+        .stderr()
+        .is("Warning: The link reference `[guide]` has no definition")
+        .unwrap();
+}
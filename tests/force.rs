@@ -0,0 +1,73 @@
+extern crate assert_cli;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use assert_cli::Assert;
+
+const EXISTING: &str = "Existing readme\n";
+
+/// Write a read-only output file in a new temporary directory
+fn read_only_output(name: &str) -> (PathBuf, PathBuf) {
+    let dir = env::temp_dir().join(format!("cargo-readme-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("README.md");
+
+    fs::write(&output, EXISTING).unwrap();
+    let mut permissions = fs::metadata(&output).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&output, permissions).unwrap();
+
+    (dir, output)
+}
+
+fn args<'a>(output: &'a Path, force: bool) -> Vec<&'a str> {
+    let mut args = vec![
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--output",
+        output.to_str().unwrap(),
+    ];
+    if force {
+        args.push("--force");
+    }
+    args
+}
+
+#[test]
+fn read_only_output_without_force_should_fail() {
+    let (dir, output) = read_only_output("read-only");
+
+    Assert::main_binary()
+        .with_args(&args(&output, false))
+        .fails()
+        .and()
+        .stderr()
+        .contains("is read-only, use `--force` to overwrite it")
+        .unwrap();
+
+    let result = fs::read_to_string(&output);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(EXISTING, result.unwrap());
+}
+
+#[test]
+fn read_only_output_with_force() {
+    let (dir, output) = read_only_output("force");
+
+    Assert::main_binary()
+        .with_args(&args(&output, true))
+        .succeeds()
+        .unwrap();
+
+    let result = fs::read_to_string(&output);
+    let readonly = fs::metadata(&output).map(|metadata| metadata.permissions().readonly());
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.unwrap().starts_with("[![Build Status]"));
+    assert!(!readonly.unwrap());
+}
@@ -0,0 +1,37 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn format_rst() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--format",
+        "rst",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("check-test\n==========\n\nTest crate for cargo-readme ``--check``\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn format_unknown_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--format",
+        "html",
+        "--stdout",
+    ];
+
+    Assert::main_binary().with_args(&args).fails().unwrap();
+}
@@ -0,0 +1,135 @@
+extern crate assert_cli;
+
+use std::env;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use assert_cli::Assert;
+
+const STALE: &str = "stale readme";
+
+fn run_if_changed(output: &Path) {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--output",
+        output.to_str().unwrap(),
+        "--if-changed",
+    ];
+
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+}
+
+/// Write a stale output, modified `offset` after the input if `newer`, or before it otherwise
+fn write_stale_output(output: &Path, offset: Duration, newer: bool) {
+    let input_modified = fs::metadata("tests/test-project/src/lib.rs")
+        .and_then(|metadata| metadata.modified())
+        .unwrap();
+    let modified = if newer {
+        input_modified + offset
+    } else {
+        input_modified - offset
+    };
+
+    fs::write(output, STALE).unwrap();
+    File::options()
+        .write(true)
+        .open(output)
+        .and_then(|file| file.set_modified(modified))
+        .unwrap();
+}
+
+#[test]
+fn if_changed_skips_newer_output() {
+    let dir = env::temp_dir().join(format!(
+        "cargo-readme-if-changed-skip-{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("README.md");
+
+    write_stale_output(&output, Duration::from_secs(3600), true);
+    run_if_changed(&output);
+
+    let result = fs::read_to_string(&output);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(STALE, result.unwrap());
+}
+
+#[test]
+fn if_changed_regenerates_older_output() {
+    let dir = env::temp_dir().join(format!("cargo-readme-if-changed-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("README.md");
+
+    write_stale_output(&output, Duration::from_secs(3600), false);
+    run_if_changed(&output);
+
+    let result = fs::read_to_string(&output);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.unwrap().starts_with("[![Build Status]"));
+}
+
+#[test]
+fn if_changed_writes_missing_output() {
+    let dir = env::temp_dir().join(format!(
+        "cargo-readme-if-changed-new-{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("README.md");
+
+    run_if_changed(&output);
+
+    let result = fs::read_to_string(&output);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.unwrap().starts_with("[![Build Status]"));
+}
+
+/// Set the modification time of a file to `age` ago
+fn set_age(path: &Path, age: Duration) {
+    File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now() - age))
+        .unwrap();
+}
+
+#[test]
+fn if_changed_regenerates_output_older_than_included_file() {
+    let dir = env::temp_dir().join(format!(
+        "cargo-readme-if-changed-include-{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::create_dir_all(dir.join("docs")).unwrap();
+    for file in &["Cargo.toml", "src/lib.rs", "docs/intro.md"] {
+        fs::copy(Path::new("tests/include-docs").join(file), dir.join(file)).unwrap();
+    }
+    let output = dir.join("README.md");
+    fs::write(&output, STALE).unwrap();
+
+    // the source is older than the output, but the file it includes is newer
+    set_age(&dir.join("src/lib.rs"), Duration::from_secs(7200));
+    set_age(&output, Duration::from_secs(3600));
+
+    let args = [
+        "readme",
+        "--project-root",
+        dir.to_str().unwrap(),
+        "--no-template",
+        "--if-changed",
+    ];
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+
+    let result = fs::read_to_string(&output);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_ne!(STALE, result.unwrap());
+}
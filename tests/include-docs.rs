@@ -0,0 +1,54 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# include-docs
+
+Crate docs written in rust
+
+## Introduction
+
+Docs kept in a markdown file
+
+```rust
+let x = 1;
+```
+
+Back in rust
+
+License: MIT
+"#;
+
+#[test]
+fn include_docs_from_file() {
+    let args = ["readme", "--project-root", "tests/include-docs", "--stdout"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn include_docs_from_itself_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/include-docs",
+        "--input",
+        "src/itself.rs",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("includes itself in its docs")
+        .unwrap();
+}
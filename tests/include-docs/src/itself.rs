@@ -0,0 +1,3 @@
+#![doc = include_str!("itself.rs")]
+
+fn main() {}
@@ -0,0 +1,7 @@
+//! Crate docs written in rust
+//!
+#![doc = include_str!("../docs/intro.md")]
+//!
+//! Back in rust
+
+pub fn f() {}
@@ -0,0 +1,59 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn license_body() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/license-body",
+        "--license-body",
+        "--stdout",
+    ];
+
+    let expected = r#"
+# license-body-test
+
+Test crate for cargo-readme `--license-body`
+
+## License
+
+MIT License
+
+Copyright (c) The license-body-test authors
+
+Apache License
+Version 2.0, January 2004
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(expected)
+        .unwrap();
+}
+
+#[test]
+fn license_body_missing_file_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--license-body",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .is(
+            "Error: Could not find the text of the license `MIT`, expected a file like \
+             'LICENSE-MIT' or 'LICENSE' in the project root",
+        )
+        .unwrap();
+}
@@ -0,0 +1,21 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn list_tags() {
+    // the `tests` directory is not a Cargo project
+    let args = ["readme", "--project-root", "tests", "--list-tags"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("{{readme}}")
+        .stdout()
+        .contains("{{crate}}")
+        .stdout()
+        .contains("{{license}}")
+        .unwrap();
+}
@@ -0,0 +1,83 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn manifest_metadata_input() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/manifest-metadata",
+        "--no-title",
+        "--no-license",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("overview")
+        .unwrap();
+}
+
+#[test]
+fn manifest_metadata_input_overridden_by_cli() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/manifest-metadata",
+        "--input",
+        "src/lib.rs",
+        "--no-title",
+        "--no-license",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("lib")
+        .unwrap();
+}
+
+#[test]
+fn manifest_metadata_options() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/manifest-options",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("### Heading\n\ntext")
+        .unwrap();
+}
+
+#[test]
+fn manifest_metadata_options_overridden_by_cli() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/manifest-options",
+        "--heading-base-level",
+        "0",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("# Heading\n\ntext")
+        .unwrap();
+}
@@ -0,0 +1 @@
+//! overview
@@ -0,0 +1,3 @@
+//! # Heading
+//!
+//! text
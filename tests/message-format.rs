@@ -0,0 +1,86 @@
+extern crate assert_cli;
+
+use std::env;
+use std::fs;
+
+use assert_cli::Assert;
+
+#[test]
+fn json_report_with_output_file() {
+    let output = env::temp_dir().join(format!(
+        "cargo-readme-message-format-{}.md",
+        std::process::id()
+    ));
+
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--output",
+        output.to_str().unwrap(),
+        "--message-format",
+        "json",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("{\"input\": \"")
+        .stdout()
+        .contains("tests/test-project/src/lib.rs\", \"output\": \"")
+        .stdout()
+        .contains("tests/test-project/README.tpl\", \"bytes_written\": ")
+        .unwrap();
+
+    let result = fs::read_to_string(&output);
+    fs::remove_file(&output).unwrap();
+
+    assert!(result.unwrap().contains("# readme-test"));
+}
+
+#[test]
+fn json_report_with_stdout() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/other.rs",
+        "--no-template",
+        "--stdout",
+        "--message-format",
+        "json",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("tests/test-project/src/other.rs\", \"output\": null, \"template\": null, \"bytes_written\": 0}")
+        .stdout()
+        .doesnt_contain("# readme-test")
+        .unwrap();
+}
+
+#[test]
+fn json_report_with_check_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--check",
+        "--message-format",
+        "json",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("`--message-format json` cannot be used with `--check` or `--diff`")
+        .unwrap();
+}
@@ -0,0 +1,54 @@
+extern crate assert_cli;
+
+use std::env;
+use std::fs;
+
+use assert_cli::Assert;
+
+#[test]
+fn mkdir_creates_output_directory() {
+    let dir = env::temp_dir().join(format!("cargo-readme-mkdir-{}", std::process::id()));
+    let output = dir.join("nested/docs/README.md");
+
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--output",
+        output.to_str().unwrap(),
+        "--mkdir",
+    ];
+
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+
+    let result = fs::read_to_string(&output);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.unwrap().starts_with("[![Build Status]"));
+}
+
+#[test]
+fn missing_output_directory_should_fail() {
+    let dir = env::temp_dir().join(format!("cargo-readme-no-mkdir-{}", std::process::id()));
+    let output = dir.join("nested/docs/README.md");
+
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--output",
+        output.to_str().unwrap(),
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("Could not create output file")
+        .unwrap();
+
+    assert!(!dir.exists());
+}
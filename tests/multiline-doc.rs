@@ -56,6 +56,7 @@ fn multiline_doc() {
         "tests/test-project",
         "--input",
         "src/multiline.rs",
+        "--stdout",
     ];
 
     Assert::main_binary()
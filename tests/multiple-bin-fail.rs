@@ -6,7 +6,12 @@ const EXPECTED: &str = "Error: Multiple binaries found, choose one: [src/entry1.
 
 #[test]
 fn multiple_bin_fail() {
-    let args = ["readme", "--project-root", "tests/multiple-bin-fail"];
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/multiple-bin-fail",
+        "--stdout",
+    ];
 
     Assert::main_binary()
         .with_args(&args)
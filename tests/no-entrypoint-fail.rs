@@ -6,7 +6,12 @@ const EXPECTED: &str = "Error: No entrypoint found";
 
 #[test]
 fn no_entrypoint_fail() {
-    let args = ["readme", "--project-root", "tests/no-entrypoint-fail"];
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/no-entrypoint-fail",
+        "--stdout",
+    ];
 
     Assert::main_binary()
         .with_args(&args)
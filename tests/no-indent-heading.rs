@@ -0,0 +1,43 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const DOCS: &str = "//! Docs
+//!
+//! # Usage
+//!
+//! Use it
+//!
+//! # Examples
+//!
+//! ## Simple
+//!
+//! An example
+";
+
+#[test]
+fn no_indent_examples_heading() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "-",
+        "--no-template",
+        "--no-title",
+        "--no-license",
+        "--no-badges",
+        "--no-indent-heading",
+        "examples",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin(DOCS)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("Docs\n\n## Usage\n\nUse it\n\n# Examples\n\n### Simple\n\nAn example")
+        .unwrap();
+}
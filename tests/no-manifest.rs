@@ -0,0 +1,62 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+// the `tests` directory has no `Cargo.toml`
+
+#[test]
+fn no_manifest_without_title() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests",
+        "--input",
+        "-",
+        "--no-title",
+        "--no-template",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin("//! Docs read from stdin")
+        .succeeds()
+        .and()
+        .stdout()
+        .is("Docs read from stdin")
+        .unwrap();
+}
+
+#[test]
+fn no_manifest_with_title_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests",
+        "--input",
+        "-",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin("//! Docs read from stdin")
+        .fails_with(1)
+        .and()
+        .stderr()
+        .contains("Could not read Cargo.toml")
+        .unwrap();
+}
+
+#[test]
+fn no_manifest_without_input_should_fail() {
+    let args = ["readme", "--project-root", "tests", "--stdout"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails_with(1)
+        .and()
+        .stderr()
+        .contains("does not look like a Rust/Cargo project")
+        .unwrap();
+}
@@ -54,6 +54,7 @@ fn no_template() {
         "tests/test-project",
         "--no-template",
         "--no-badges",
+        "--stdout",
     ];
 
     Assert::main_binary()
@@ -0,0 +1,42 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn readme_disabled_is_skipped() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/package-readme/disabled",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("")
+        .and()
+        .stderr()
+        .is("Skipping readme-disabled-test, `readme` is `false` in Cargo.toml")
+        .unwrap();
+}
+
+#[test]
+fn readme_path_is_default_output() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/package-readme/path",
+        "--check",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("")
+        .unwrap();
+}
@@ -0,0 +1 @@
+//! Test crate for `readme = false`
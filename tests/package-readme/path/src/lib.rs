@@ -0,0 +1 @@
+//! Test crate for `readme = "docs/README.md"`
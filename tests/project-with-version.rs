@@ -16,6 +16,7 @@ fn template_with_version() {
         "tests/project-with-version",
         "--template",
         "README.tpl",
+        "--stdout",
     ];
 
     Assert::main_binary()
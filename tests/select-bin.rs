@@ -0,0 +1,58 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+fn readme_of_bin(name: &str, docs: &str) {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/select-bin",
+        "--bin",
+        name,
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(&*format!("# select-bin\n\n{}\n\nLicense: MIT", docs))
+        .unwrap();
+}
+
+#[test]
+fn select_bin_from_manifest() {
+    readme_of_bin("entry", "Docs of the entry binary");
+}
+
+#[test]
+fn select_bin_with_package_name() {
+    readme_of_bin("select-bin", "Docs of the main binary");
+}
+
+#[test]
+fn select_bin_in_bin_directory() {
+    readme_of_bin("tool", "Docs of the tool binary");
+    readme_of_bin("server", "Docs of the server binary");
+}
+
+#[test]
+fn select_missing_bin_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/select-bin",
+        "--bin",
+        "missing",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .is("Error: No binary named `missing`, choose one of: entry, select-bin, server, tool")
+        .unwrap();
+}
@@ -0,0 +1 @@
+//! Docs of the tool binary
@@ -0,0 +1,29 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn standalone_without_subcommand() {
+    let args = ["--project-root", "tests/check", "--stdout"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("# check-test\n\nTest crate for cargo-readme `--check`\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn standalone_with_subcommand() {
+    let args = ["readme", "--project-root", "tests/check", "--stdout"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("# check-test\n\nTest crate for cargo-readme `--check`\n\nLicense: MIT")
+        .unwrap();
+}
@@ -0,0 +1,56 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn stdin_input() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--input",
+        "-",
+        "--stdout",
+    ];
+
+    let expected = r#"
+# readme-test
+
+Read from stdin
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin("//! Read from stdin\nfn main() {}\n")
+        .succeeds()
+        .and()
+        .stdout()
+        .is(expected)
+        .unwrap();
+}
+
+#[test]
+fn stdin_template_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "-",
+        "--template",
+        "-",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .is("Error: Template cannot be read from stdin")
+        .unwrap();
+}
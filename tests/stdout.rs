@@ -0,0 +1,53 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn stdout_prints_readme() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--no-badges",
+        "--input",
+        "src/single_line.rs",
+        "--stdout",
+    ];
+
+    let expected = r#"
+# readme-test
+
+Test crate for cargo-readme
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(expected)
+        .unwrap();
+}
+
+#[test]
+fn stdout_with_output_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--stdout",
+        "--output",
+        "README.md",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("cannot be used with")
+        .unwrap();
+}
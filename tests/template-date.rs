@@ -0,0 +1,84 @@
+extern crate assert_cli;
+
+use assert_cli::{Assert, Environment};
+
+#[test]
+fn date_tag_with_current_date() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "DATE.tpl",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("Generated on 2")
+        .stdout()
+        .doesnt_contain("{{date}}")
+        .unwrap();
+}
+
+#[test]
+fn date_tag_pinned_by_env() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "DATE.tpl",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .with_env(Environment::inherit().insert("CARGO_README_DATE", "2024-05-01"))
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("Generated on 2024-05-01")
+        .unwrap();
+}
+
+#[test]
+fn date_tag_kept_by_check() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "DATE.tpl",
+        "--output",
+        "DATE.md",
+        "--check",
+    ];
+
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+}
+
+#[test]
+fn date_tag_kept_by_diff() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "DATE.tpl",
+        "--output",
+        "DATE.md",
+        "--diff",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("")
+        .unwrap();
+}
@@ -0,0 +1,47 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = "# member
+
+A workspace member without its own template.
+
+Part of the template-workspace workspace.";
+
+#[test]
+fn template_from_workspace_root() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/template-workspace/crates/member",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn explicit_template_is_relative_to_project_root() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/template-workspace/crates/member",
+        "--template",
+        "README.tpl",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("Could not open template file")
+        .unwrap();
+}
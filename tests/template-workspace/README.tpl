@@ -0,0 +1,5 @@
+# {{crate}}
+
+{{readme}}
+
+Part of the template-workspace workspace.
@@ -0,0 +1 @@
+//! A workspace member without its own template.
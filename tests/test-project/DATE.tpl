@@ -0,0 +1,3 @@
+{{readme}}
+
+Generated on {{date}}
@@ -0,0 +1,5 @@
+# {{crate}}
+
+{{toc}}
+
+{{readme}}
@@ -0,0 +1,5 @@
+Template with an unknown tag.
+
+{{unknown}}
+
+{{readme}}
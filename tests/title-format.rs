@@ -0,0 +1,96 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn title_format_default() {
+    let args = ["readme", "--project-root", "tests/check", "--stdout"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("# check-test\n\nTest crate for cargo-readme `--check`\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn title_format_single_new_line() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--title-format",
+        "# {name}\\n",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("# check-test\nTest crate for cargo-readme `--check`\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn title_format_without_name_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--title-format",
+        "# Title\\n\\n",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("Title format must contain `{name}`")
+        .unwrap();
+}
+
+#[test]
+fn title_case() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/check",
+        "--title-case",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("# Check Test\n\nTest crate for cargo-readme `--check`\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn title_case_in_template() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "TOC.tpl",
+        "--title-case",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("# Readme Test\n\n")
+        .unwrap();
+}
@@ -0,0 +1,57 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const DOCS: &str = "//! Docs
+//!
+//! ```
+//! let x = 1;
+";
+
+#[test]
+fn unclosed_fence_is_closed() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "-",
+        "--no-template",
+        "--no-badges",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin(DOCS)
+        .succeeds()
+        .and()
+        .stdout()
+        .is("# readme-test\n\nDocs\n\n```rust\nlet x = 1;\n```\n\nLicense: MIT")
+        .stderr()
+        .contains("Warning: A code block is not closed at the end of the docs, it is closed with `")
+        .unwrap();
+}
+
+#[test]
+fn unclosed_fence_with_strict_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "-",
+        "--no-template",
+        "--strict",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .stdin(DOCS)
+        .fails()
+        .and()
+        .stderr()
+        .contains("A code block is not closed at the end of the docs")
+        .unwrap();
+}
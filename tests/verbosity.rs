@@ -0,0 +1,97 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn verbose_prints_files_used() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "OTHER.tpl",
+        "--verbose",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("Other readme template.")
+        .and()
+        .stderr()
+        .contains("Reading docs from '")
+        .and()
+        .stderr()
+        .contains("OTHER.tpl'")
+        .and()
+        .stderr()
+        .contains("Writing output to stdout")
+        .unwrap();
+}
+
+#[test]
+fn unknown_tag_warning() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "UNKNOWN.tpl",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stderr()
+        .is("Warning: Unknown tags found in template: `{{unknown}}`")
+        .unwrap();
+}
+
+#[test]
+fn quiet_hides_warnings() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "UNKNOWN.tpl",
+        "-q",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .and()
+        .stdout()
+        .contains("Template with an unknown tag.")
+        .and()
+        .stderr()
+        .is("")
+        .unwrap();
+}
+
+#[test]
+fn quiet_and_verbose_should_fail() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--quiet",
+        "--verbose",
+        "--stdout",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .and()
+        .stderr()
+        .contains("cannot be used with")
+        .unwrap();
+}